@@ -0,0 +1,52 @@
+//! Clocks stamping updates, i.e. `now()` in the paper's specs.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub type Timestamp = u64;
+
+pub trait Clock {
+    fn now(&mut self) -> Timestamp;
+}
+
+/// Wall-clock time in nanoseconds since the Unix epoch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&mut self) -> Timestamp {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos() as Timestamp)
+    }
+}
+
+/// Deterministic clock ticking by one on every call, starting after `time`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LogicalClock {
+    time: Timestamp,
+}
+
+impl LogicalClock {
+    pub fn new(time: Timestamp) -> Self {
+        Self { time }
+    }
+}
+
+impl Clock for LogicalClock {
+    fn now(&mut self) -> Timestamp {
+        self.time += 1;
+        self.time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_logical_clock() {
+        let mut clock = LogicalClock::new(5);
+        assert_eq!(clock.now(), 6);
+        assert_eq!(clock.now(), 7);
+    }
+}
@@ -1,2 +1,6 @@
+pub mod clock;
 pub mod ops_based;
 pub mod state_based;
+
+/// Identifier of a replica, i.e. `myID()` in the paper's specs.
+pub type ReplicaId = u64;
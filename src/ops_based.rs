@@ -15,6 +15,32 @@
 //!     2nd phase, asynchronous, side-effects to downstream state
 //! ```
 
+pub mod add_remove_partial_order;
+pub mod bounded_counter;
+pub mod counter;
+pub mod g_set;
+pub mod lww_register;
+pub mod monotonic_dag;
+pub mod or_set;
+pub mod rga;
+pub mod two_p_set;
+pub mod two_p_two_p_graph;
+pub mod u_set;
+
+pub use add_remove_partial_order::{
+    AddRemovePartialOrder, AddRemovePartialOrderError, AddRemovePartialOrderOp,
+};
+pub use bounded_counter::{BoundedCounter, BoundedCounterError, BoundedCounterOp};
+pub use counter::{Counter, CounterOp};
+pub use g_set::GSet;
+pub use lww_register::{LwwAssign, LwwRegister};
+pub use monotonic_dag::{MonotonicDag, MonotonicDagError, MonotonicDagOp, Vertex};
+pub use or_set::{ORSet, ORSetError, ORSetOp};
+pub use rga::{Rga, RgaError, RgaOp, RgaTimestamp};
+pub use two_p_set::{TwoPSet, TwoPSetError, TwoPSetOp};
+pub use two_p_two_p_graph::{TwoPTwoPGraph, TwoPTwoPGraphError, TwoPTwoPGraphOp};
+pub use u_set::{USet, USetError, USetOp};
+
 pub trait OpsBased<T> {
     type Query: FnOnce(&T) -> Option<T>;
     type Args;
@@ -57,9 +83,7 @@ where
         at_source: T::AtSource,
         downstream: T::Downstream,
     ) -> Result<Option<T>, T::Error> {
-        let res = at_source(&mut self.initial, args);
-        downstream(&mut self.initial, args);
-        Ok(res)
+        self.initial.update(args, at_source, downstream)
     }
 }
 
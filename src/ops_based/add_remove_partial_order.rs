@@ -0,0 +1,274 @@
+//! Operation-based add-remove partial order
+//!
+//! Extends the [`MonotonicDag`](super::MonotonicDag) with vertex removal.
+//! Removed vertices stay in the graph as tombstones, so paths through them
+//! still order the vertices inserted later, and an insert next to a vertex
+//! removed concurrently remains well defined.
+//!
+//! ```txt
+//! payload set V, set R, set E // V: vertices; R: removed; E: edges
+//!   initial {⊢, ⊣}, ∅, {(⊢, ⊣)}
+//! query lookup (vertex v) : boolean b
+//!   let b = (v ∈ V \ R)
+//! query before (vertex u, v) : boolean b
+//!   pre lookup(u) ∧ lookup(v)
+//!   let b = (∃w1, ..., wm ∈ V : w1 = u ∧ wm = v ∧ ∀j : (wj, wj+1) ∈ E)
+//! update addBetween (vertex u, v, w)
+//!   atSource (u, v, w)
+//!     pre lookup(u) ∧ lookup(w)
+//!     pre before(u, w)
+//!     pre v ∉ V // v is fresh
+//!   downstream (u, v, w)
+//!     V := V ∪ {v}
+//!     E := E ∪ {(u, v), (v, w)}
+//! update remove (vertex v)
+//!   atSource (v)
+//!     pre lookup(v)
+//!     pre v ≠ ⊢ ∧ v ≠ ⊣ // Sentinels are never removed
+//!   downstream (v)
+//!     pre addBetween(_, v, _) delivered
+//!     R := R ∪ {v}
+//! ```
+
+use std::{collections::BTreeSet, error, fmt};
+
+use super::{monotonic_dag::path, OpsBased, Vertex};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddRemovePartialOrderError {
+    VertexNotFound,
+    VertexExists,
+    /// The new vertex would close a cycle.
+    WouldCreateCycle,
+    /// The neighbours are not ordered yet, so the vertex would add a new order.
+    Unordered,
+}
+
+impl fmt::Display for AddRemovePartialOrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::VertexNotFound => write!(f, "vertex is not present"),
+            Self::VertexExists => write!(f, "vertex was already added"),
+            Self::WouldCreateCycle => write!(f, "vertex would create a cycle"),
+            Self::Unordered => write!(f, "vertices are not ordered by an existing path"),
+        }
+    }
+}
+
+impl error::Error for AddRemovePartialOrderError {}
+
+/// `Remove` names an inner vertex, so the sentinels cannot be removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddRemovePartialOrderOp<T> {
+    AddBetween(Vertex<T>, T, Vertex<T>),
+    Remove(T),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddRemovePartialOrder<T> {
+    vertices: BTreeSet<Vertex<T>>,
+    removed: BTreeSet<Vertex<T>>,
+    edges: BTreeSet<(Vertex<T>, Vertex<T>)>,
+}
+
+impl<T> Default for AddRemovePartialOrder<T>
+where
+    T: Ord,
+{
+    fn default() -> Self {
+        Self {
+            vertices: BTreeSet::from([Vertex::Start, Vertex::End]),
+            removed: BTreeSet::new(),
+            edges: BTreeSet::from([(Vertex::Start, Vertex::End)]),
+        }
+    }
+}
+
+impl<T> AddRemovePartialOrder<T>
+where
+    T: Ord + Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lookup(&self, vertex: &Vertex<T>) -> bool {
+        self.vertices.contains(vertex) && !self.removed.contains(vertex)
+    }
+
+    /// Vertices that are present, i.e. neither removed nor a sentinel.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.vertices
+            .difference(&self.removed)
+            .filter_map(|vertex| match vertex {
+                Vertex::Inner(value) => Some(value),
+                Vertex::Start | Vertex::End => None,
+            })
+    }
+
+    /// Paths may run through removed vertices.
+    pub fn before(&self, u: &Vertex<T>, v: &Vertex<T>) -> Result<bool, AddRemovePartialOrderError> {
+        if !self.lookup(u) || !self.lookup(v) {
+            return Err(AddRemovePartialOrderError::VertexNotFound);
+        }
+        Ok(path(&self.edges, u, v))
+    }
+
+    /// Neither update returns anything at the source.
+    pub fn at_source(
+        &mut self,
+        _op: &AddRemovePartialOrderOp<T>,
+    ) -> Option<AddRemovePartialOrder<T>> {
+        None
+    }
+
+    pub fn downstream(&mut self, op: &AddRemovePartialOrderOp<T>) {
+        match op {
+            AddRemovePartialOrderOp::AddBetween(u, v, w) => {
+                let v = Vertex::Inner(v.clone());
+                self.vertices.insert(v.clone());
+                self.edges.insert((u.clone(), v.clone()));
+                self.edges.insert((v, w.clone()));
+            }
+            AddRemovePartialOrderOp::Remove(v) => {
+                self.removed.insert(Vertex::Inner(v.clone()));
+            }
+        }
+    }
+}
+
+impl<T> OpsBased<AddRemovePartialOrder<T>> for AddRemovePartialOrder<T>
+where
+    T: Ord + Clone,
+{
+    type Query = fn(&AddRemovePartialOrder<T>) -> Option<AddRemovePartialOrder<T>>;
+    type Args = AddRemovePartialOrderOp<T>;
+    type AtSource =
+        fn(&mut AddRemovePartialOrder<T>, &Self::Args) -> Option<AddRemovePartialOrder<T>>;
+    type Downstream = fn(&mut AddRemovePartialOrder<T>, &Self::Args);
+    type Error = AddRemovePartialOrderError;
+
+    fn query(&self, query: Self::Query) -> Result<Option<AddRemovePartialOrder<T>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(
+        &mut self,
+        args: &Self::Args,
+        at_source: Self::AtSource,
+        downstream: Self::Downstream,
+    ) -> Result<Option<AddRemovePartialOrder<T>>, Self::Error> {
+        match args {
+            AddRemovePartialOrderOp::AddBetween(u, v, w) => {
+                if !self.before(u, w)? {
+                    return Err(if u == w || self.before(w, u)? {
+                        AddRemovePartialOrderError::WouldCreateCycle
+                    } else {
+                        AddRemovePartialOrderError::Unordered
+                    });
+                }
+                if self.vertices.contains(&Vertex::Inner(v.clone())) {
+                    return Err(AddRemovePartialOrderError::VertexExists);
+                }
+            }
+            AddRemovePartialOrderOp::Remove(v) => {
+                if !self.lookup(&Vertex::Inner(v.clone())) {
+                    return Err(AddRemovePartialOrderError::VertexNotFound);
+                }
+            }
+        }
+        let res = at_source(self, args);
+        downstream(self, args);
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops_based::Payload;
+
+    fn update(
+        payload: &mut Payload<AddRemovePartialOrder<char>>,
+        op: AddRemovePartialOrderOp<char>,
+    ) -> Result<(), AddRemovePartialOrderError> {
+        payload
+            .update(
+                &op,
+                AddRemovePartialOrder::at_source,
+                AddRemovePartialOrder::downstream,
+            )
+            .map(|_| ())
+    }
+
+    #[test]
+    fn test_update() {
+        let mut payload = Payload::new(AddRemovePartialOrder::new());
+        let ops = [
+            AddRemovePartialOrderOp::AddBetween(Vertex::Start, 'a', Vertex::End),
+            AddRemovePartialOrderOp::AddBetween(Vertex::Inner('a'), 'b', Vertex::End),
+            AddRemovePartialOrderOp::Remove('a'),
+            // Still ordered through the removed 'a'.
+            AddRemovePartialOrderOp::AddBetween(Vertex::Start, 'c', Vertex::Inner('b')),
+        ];
+        for op in ops {
+            update(&mut payload, op).unwrap();
+        }
+
+        let order = payload.query(|order| Some(order.clone())).unwrap().unwrap();
+        assert!(!order.lookup(&Vertex::Inner('a')));
+        assert_eq!(order.iter().collect::<Vec<_>>(), vec![&'b', &'c']);
+        assert_eq!(
+            order.before(&Vertex::Inner('c'), &Vertex::Inner('b')),
+            Ok(true)
+        );
+    }
+
+    #[test]
+    fn test_update_preconditions() {
+        let mut payload = Payload::new(AddRemovePartialOrder::new());
+        let add = AddRemovePartialOrderOp::AddBetween(Vertex::Start, 'a', Vertex::End);
+        update(&mut payload, add.clone()).unwrap();
+        update(&mut payload, AddRemovePartialOrderOp::Remove('a')).unwrap();
+        assert_eq!(
+            update(&mut payload, add),
+            Err(AddRemovePartialOrderError::VertexExists)
+        );
+        assert_eq!(
+            update(&mut payload, AddRemovePartialOrderOp::Remove('a')),
+            Err(AddRemovePartialOrderError::VertexNotFound)
+        );
+        assert_eq!(
+            update(
+                &mut payload,
+                AddRemovePartialOrderOp::AddBetween(Vertex::End, 'b', Vertex::Start)
+            ),
+            Err(AddRemovePartialOrderError::WouldCreateCycle)
+        );
+    }
+
+    #[test]
+    fn test_concurrent_remove_and_insert_next_to_it() {
+        let mut replica1 = AddRemovePartialOrder::new();
+        replica1.downstream(&AddRemovePartialOrderOp::AddBetween(
+            Vertex::Start,
+            'a',
+            Vertex::End,
+        ));
+        let mut replica2 = replica1.clone();
+
+        let remove = AddRemovePartialOrderOp::Remove('a');
+        let insert = AddRemovePartialOrderOp::AddBetween(Vertex::Inner('a'), 'b', Vertex::End);
+        replica1.downstream(&remove);
+        replica2.downstream(&insert);
+        replica1.downstream(&insert);
+        replica2.downstream(&remove);
+
+        assert_eq!(replica1, replica2);
+        assert_eq!(replica1.iter().collect::<Vec<_>>(), vec![&'b']);
+        assert_eq!(
+            replica1.before(&Vertex::Start, &Vertex::Inner('b')),
+            Ok(true)
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! Operation-based bounded counter
+//!
+//! A counter whose value never drops below zero. Every increment grants
+//! rights to the replica issuing it, a replica may only decrement by the
+//! rights it currently holds, and rights can be transferred to other
+//! replicas that run out of them. Only the owner ever consumes its rights, so
+//! the precondition checked at the source keeps holding downstream.
+//!
+//! ```txt
+//! payload integer v, integer[n] R
+//!   initial 0, [0, ..., 0]
+//! query value () : integer j
+//!   let j = v
+//! update increment (replica i, integer n)
+//!   downstream (i, n)
+//!     v := v + n
+//!     R[i] := R[i] + n
+//! update decrement (replica i, integer n)
+//!   atSource (i, n)
+//!     pre R[i] ≥ n
+//!   downstream (i, n) // Causal delivery: rights of i were delivered first
+//!     v := v - n
+//!     R[i] := R[i] - n
+//! update transfer (replica i, replica j, integer n)
+//!   atSource (i, j, n)
+//!     pre R[i] ≥ n
+//!   downstream (i, j, n) // Causal delivery: rights of i were delivered first
+//!     R[i] := R[i] - n
+//!     R[j] := R[j] + n
+//! ```
+
+use std::{collections::BTreeMap, error, fmt};
+
+use super::OpsBased;
+use crate::ReplicaId;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoundedCounterError {
+    InsufficientRights {
+        replica: ReplicaId,
+        requested: u64,
+        available: u64,
+    },
+}
+
+impl fmt::Display for BoundedCounterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InsufficientRights {
+                replica,
+                requested,
+                available,
+            } => write!(
+                f,
+                "replica {replica} requested {requested} rights but only holds {available}"
+            ),
+        }
+    }
+}
+
+impl error::Error for BoundedCounterError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundedCounterOp {
+    Increment {
+        replica: ReplicaId,
+        amount: u64,
+    },
+    Decrement {
+        replica: ReplicaId,
+        amount: u64,
+    },
+    Transfer {
+        from: ReplicaId,
+        to: ReplicaId,
+        amount: u64,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BoundedCounter {
+    value: u64,
+    rights: BTreeMap<ReplicaId, u64>,
+}
+
+impl BoundedCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    pub fn local_rights(&self, replica: ReplicaId) -> u64 {
+        self.rights.get(&replica).copied().unwrap_or(0)
+    }
+
+    fn withdraw(&mut self, replica: ReplicaId, amount: u64) {
+        let rights = self.rights.entry(replica).or_insert(0);
+        *rights = rights
+            .checked_sub(amount)
+            .expect("insufficient rights downstream");
+    }
+
+    /// None of the updates return anything at the source.
+    pub fn at_source(&mut self, _op: &BoundedCounterOp) -> Option<BoundedCounter> {
+        None
+    }
+
+    /// # Panics
+    ///
+    /// Panics if a decrement or transfer exceeds the rights it was checked
+    /// against at the source, i.e. if ops are not delivered in causal order.
+    pub fn downstream(&mut self, op: &BoundedCounterOp) {
+        match *op {
+            BoundedCounterOp::Increment { replica, amount } => {
+                self.value += amount;
+                *self.rights.entry(replica).or_insert(0) += amount;
+            }
+            BoundedCounterOp::Decrement { replica, amount } => {
+                self.value = self.value.checked_sub(amount).expect("value below zero");
+                self.withdraw(replica, amount);
+            }
+            BoundedCounterOp::Transfer { from, to, amount } => {
+                self.withdraw(from, amount);
+                *self.rights.entry(to).or_insert(0) += amount;
+            }
+        }
+    }
+}
+
+impl OpsBased<BoundedCounter> for BoundedCounter {
+    type Query = fn(&BoundedCounter) -> Option<BoundedCounter>;
+    type Args = BoundedCounterOp;
+    type AtSource = fn(&mut BoundedCounter, &Self::Args) -> Option<BoundedCounter>;
+    type Downstream = fn(&mut BoundedCounter, &Self::Args);
+    type Error = BoundedCounterError;
+
+    fn query(&self, query: Self::Query) -> Result<Option<BoundedCounter>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(
+        &mut self,
+        args: &Self::Args,
+        at_source: Self::AtSource,
+        downstream: Self::Downstream,
+    ) -> Result<Option<BoundedCounter>, Self::Error> {
+        if let BoundedCounterOp::Decrement { replica, amount }
+        | BoundedCounterOp::Transfer {
+            from: replica,
+            amount,
+            ..
+        } = *args
+        {
+            let available = self.local_rights(replica);
+            if amount > available {
+                return Err(BoundedCounterError::InsufficientRights {
+                    replica,
+                    requested: amount,
+                    available,
+                });
+            }
+        }
+        let res = at_source(self, args);
+        downstream(self, args);
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops_based::Payload;
+
+    fn update(
+        payload: &mut Payload<BoundedCounter>,
+        op: BoundedCounterOp,
+    ) -> Result<Option<BoundedCounter>, BoundedCounterError> {
+        payload.update(&op, BoundedCounter::at_source, BoundedCounter::downstream)
+    }
+
+    #[test]
+    fn test_update() {
+        let mut payload = Payload::new(BoundedCounter::new());
+        update(
+            &mut payload,
+            BoundedCounterOp::Increment {
+                replica: 0,
+                amount: 2,
+            },
+        )
+        .unwrap();
+        update(
+            &mut payload,
+            BoundedCounterOp::Transfer {
+                from: 0,
+                to: 1,
+                amount: 1,
+            },
+        )
+        .unwrap();
+        update(
+            &mut payload,
+            BoundedCounterOp::Decrement {
+                replica: 1,
+                amount: 1,
+            },
+        )
+        .unwrap();
+
+        let counter = payload.query(|counter| Some(counter.clone())).unwrap();
+        let counter = counter.unwrap();
+        assert_eq!(counter.value(), 1);
+        assert_eq!(counter.local_rights(0), 1);
+        assert_eq!(counter.local_rights(1), 0);
+    }
+
+    #[test]
+    fn test_update_insufficient_rights() {
+        let mut payload = Payload::new(BoundedCounter::new());
+        update(
+            &mut payload,
+            BoundedCounterOp::Increment {
+                replica: 0,
+                amount: 1,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            update(
+                &mut payload,
+                BoundedCounterOp::Decrement {
+                    replica: 1,
+                    amount: 1,
+                },
+            ),
+            Err(BoundedCounterError::InsufficientRights {
+                replica: 1,
+                requested: 1,
+                available: 0,
+            })
+        );
+        assert!(update(
+            &mut payload,
+            BoundedCounterOp::Transfer {
+                from: 0,
+                to: 1,
+                amount: 2,
+            },
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,111 @@
+//! Operation-based counter
+//!
+//! ```txt
+//! payload integer i
+//!   initial 0
+//! query value () : integer j
+//!   let j = i
+//! update increment ()
+//!   downstream () // No precond: delivery order is empty
+//!     i := i + 1
+//! update decrement ()
+//!   downstream () // No precond: delivery order is empty
+//!     i := i - 1
+//! ```
+
+use std::convert::Infallible;
+
+use super::OpsBased;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CounterOp {
+    Increment,
+    Decrement,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Counter {
+    i: i64,
+}
+
+impl Counter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn value(&self) -> i64 {
+        self.i
+    }
+
+    /// Neither update returns anything at the source.
+    pub fn at_source(&mut self, _op: &CounterOp) -> Option<Counter> {
+        None
+    }
+
+    pub fn downstream(&mut self, op: &CounterOp) {
+        match op {
+            CounterOp::Increment => self.i += 1,
+            CounterOp::Decrement => self.i -= 1,
+        }
+    }
+}
+
+impl OpsBased<Counter> for Counter {
+    type Query = fn(&Counter) -> Option<Counter>;
+    type Args = CounterOp;
+    type AtSource = fn(&mut Counter, &Self::Args) -> Option<Counter>;
+    type Downstream = fn(&mut Counter, &Self::Args);
+    type Error = Infallible;
+
+    fn query(&self, query: Self::Query) -> Result<Option<Counter>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(
+        &mut self,
+        args: &Self::Args,
+        at_source: Self::AtSource,
+        downstream: Self::Downstream,
+    ) -> Result<Option<Counter>, Self::Error> {
+        let res = at_source(self, args);
+        downstream(self, args);
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops_based::Payload;
+
+    #[test]
+    fn test_update() {
+        let mut payload = Payload::new(Counter::new());
+        for op in [
+            CounterOp::Increment,
+            CounterOp::Increment,
+            CounterOp::Decrement,
+        ] {
+            payload
+                .update(&op, Counter::at_source, Counter::downstream)
+                .unwrap();
+        }
+        let counter = payload.query(|counter| Some(counter.clone())).unwrap();
+        assert_eq!(counter.unwrap().value(), 1);
+    }
+
+    #[test]
+    fn test_downstream_commutes() {
+        let ops = [
+            CounterOp::Increment,
+            CounterOp::Decrement,
+            CounterOp::Increment,
+        ];
+        let mut replica1 = Counter::new();
+        let mut replica2 = Counter::new();
+        ops.iter().for_each(|op| replica1.downstream(op));
+        ops.iter().rev().for_each(|op| replica2.downstream(op));
+        assert_eq!(replica1, replica2);
+        assert_eq!(replica1.value(), 1);
+    }
+}
@@ -0,0 +1,103 @@
+//! Operation-based grow-only set (G-Set)
+//!
+//! ```txt
+//! payload set A
+//!   initial ∅
+//! query lookup (element e) : boolean b
+//!   let b = (e ∈ A)
+//! update add (element e)
+//!   atSource (e)
+//!   downstream (e) // No precond: delivery order is empty
+//!     A := A ∪ {e}
+//! ```
+
+use std::{collections::BTreeSet, convert::Infallible};
+
+use super::OpsBased;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GSet<T> {
+    a: BTreeSet<T>,
+}
+
+impl<T> Default for GSet<T> {
+    fn default() -> Self {
+        Self { a: BTreeSet::new() }
+    }
+}
+
+impl<T> GSet<T>
+where
+    T: Ord + Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lookup(&self, element: &T) -> bool {
+        self.a.contains(element)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.a.iter()
+    }
+
+    /// `add` returns nothing at the source.
+    pub fn at_source(&mut self, _element: &T) -> Option<GSet<T>> {
+        None
+    }
+
+    pub fn downstream(&mut self, element: &T) {
+        self.a.insert(element.clone());
+    }
+}
+
+impl<T> OpsBased<GSet<T>> for GSet<T> {
+    type Query = fn(&GSet<T>) -> Option<GSet<T>>;
+    type Args = T;
+    type AtSource = fn(&mut GSet<T>, &Self::Args) -> Option<GSet<T>>;
+    type Downstream = fn(&mut GSet<T>, &Self::Args);
+    type Error = Infallible;
+
+    fn query(&self, query: Self::Query) -> Result<Option<GSet<T>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(
+        &mut self,
+        args: &Self::Args,
+        at_source: Self::AtSource,
+        downstream: Self::Downstream,
+    ) -> Result<Option<GSet<T>>, Self::Error> {
+        let res = at_source(self, args);
+        downstream(self, args);
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops_based::Payload;
+
+    #[test]
+    fn test_update() {
+        let mut payload = Payload::new(GSet::new());
+        payload
+            .update(&1, GSet::at_source, GSet::downstream)
+            .unwrap();
+        let set = payload.query(|set| Some(set.clone())).unwrap().unwrap();
+        assert!(set.lookup(&1));
+        assert!(!set.lookup(&2));
+    }
+
+    #[test]
+    fn test_downstream_commutes() {
+        let mut replica1 = GSet::new();
+        let mut replica2 = GSet::new();
+        [1, 2, 1].iter().for_each(|e| replica1.downstream(e));
+        [2, 1].iter().for_each(|e| replica2.downstream(e));
+        assert_eq!(replica1, replica2);
+        assert_eq!(replica1.iter().collect::<Vec<_>>(), vec![&1, &2]);
+    }
+}
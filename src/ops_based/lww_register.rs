@@ -0,0 +1,173 @@
+//! Operation-based last-writer-wins register (LWW-Register)
+//!
+//! Ties between equal timestamps are broken by the id of the writing replica.
+//!
+//! `AtSource` only sees the op through a shared reference, so it cannot write
+//! the timestamp `t'` into it. The source step is done by
+//! [`LwwRegister::assign_op`] instead, which stamps the assignment from the
+//! register it is prepared on, and `AtSource` is a no-op.
+//!
+//! ```txt
+//! payload X x, timestamp t // X: some type
+//!   initial ⊥, 0
+//! query value () : X w
+//!   let w = x
+//! update assign (X x')
+//!   atSource () : t'
+//!     let t' = now() // Timestamp
+//!   downstream (x', t') // No precond: delivery order is empty
+//!     if t < t' then x, t := x', t'
+//! ```
+
+use std::convert::Infallible;
+
+use super::OpsBased;
+use crate::{
+    clock::{Clock, Timestamp},
+    ReplicaId,
+};
+
+/// An assignment stamped at the source, ready to be shipped downstream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LwwAssign<V> {
+    value: V,
+    timestamp: Timestamp,
+    replica: ReplicaId,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LwwRegister<V> {
+    value: V,
+    timestamp: Timestamp,
+    replica: ReplicaId,
+}
+
+impl<V> LwwRegister<V> {
+    /// `initial` plays the role of `⊥`, stamped with timestamp `0`.
+    pub fn new(initial: V) -> Self {
+        Self {
+            value: initial,
+            timestamp: 0,
+            replica: 0,
+        }
+    }
+
+    pub fn value(&self) -> &V {
+        &self.value
+    }
+
+    pub fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+
+    /// Prepares an assignment stamped strictly after the current value, so a
+    /// local assign is never dropped because the clock lags behind.
+    pub fn assign_op<C: Clock>(&self, replica: ReplicaId, value: V, clock: &mut C) -> LwwAssign<V> {
+        LwwAssign {
+            value,
+            timestamp: clock.now().max(self.timestamp + 1),
+            replica,
+        }
+    }
+}
+
+impl<V> LwwRegister<V>
+where
+    V: Clone,
+{
+    /// The assignment is stamped by [`LwwRegister::assign_op`], so there is
+    /// nothing left to return at the source.
+    pub fn at_source(&mut self, _op: &LwwAssign<V>) -> Option<LwwRegister<V>> {
+        None
+    }
+
+    pub fn downstream(&mut self, op: &LwwAssign<V>) {
+        if (self.timestamp, self.replica) < (op.timestamp, op.replica) {
+            self.value = op.value.clone();
+            self.timestamp = op.timestamp;
+            self.replica = op.replica;
+        }
+    }
+}
+
+impl<V> OpsBased<LwwRegister<V>> for LwwRegister<V> {
+    type Query = fn(&LwwRegister<V>) -> Option<LwwRegister<V>>;
+    type Args = LwwAssign<V>;
+    type AtSource = fn(&mut LwwRegister<V>, &Self::Args) -> Option<LwwRegister<V>>;
+    type Downstream = fn(&mut LwwRegister<V>, &Self::Args);
+    type Error = Infallible;
+
+    fn query(&self, query: Self::Query) -> Result<Option<LwwRegister<V>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(
+        &mut self,
+        args: &Self::Args,
+        at_source: Self::AtSource,
+        downstream: Self::Downstream,
+    ) -> Result<Option<LwwRegister<V>>, Self::Error> {
+        let res = at_source(self, args);
+        downstream(self, args);
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{clock::LogicalClock, ops_based::Payload};
+
+    #[test]
+    fn test_update() {
+        let mut payload = Payload::new(LwwRegister::new(""));
+        let snapshot = |payload: &Payload<LwwRegister<&'static str>>| {
+            payload
+                .query(|register| Some(register.clone()))
+                .unwrap()
+                .unwrap()
+        };
+
+        // The second clock lags behind the first, yet the later assign wins.
+        for (value, mut clock) in [("a", LogicalClock::new(5)), ("b", LogicalClock::default())] {
+            let op = snapshot(&payload).assign_op(0, value, &mut clock);
+            payload
+                .update(&op, LwwRegister::at_source, LwwRegister::downstream)
+                .unwrap();
+        }
+        assert_eq!(*snapshot(&payload).value(), "b");
+        assert_eq!(snapshot(&payload).timestamp(), 7);
+    }
+
+    #[test]
+    fn test_downstream_commutes() {
+        let initial = LwwRegister::new("");
+        let older = initial.assign_op(0, "a", &mut LogicalClock::new(0));
+        let newer = initial.assign_op(1, "b", &mut LogicalClock::new(1));
+        let tie = initial.assign_op(2, "c", &mut LogicalClock::new(1));
+
+        let mut replica1 = LwwRegister::new("");
+        let mut replica2 = LwwRegister::new("");
+        [&older, &newer, &tie]
+            .into_iter()
+            .for_each(|op| replica1.downstream(op));
+        [&tie, &newer, &older]
+            .into_iter()
+            .for_each(|op| replica2.downstream(op));
+        assert_eq!(replica1, replica2);
+        assert_eq!(*replica1.value(), "c");
+        assert_eq!(replica1.timestamp(), 2);
+    }
+
+    #[test]
+    fn test_assign_op_lagging_clock() {
+        let mut register = LwwRegister::new("");
+        let remote = register.assign_op(1, "a", &mut LogicalClock::new(5));
+        register.downstream(&remote);
+
+        let local = register.assign_op(0, "b", &mut LogicalClock::default());
+        register.downstream(&local);
+        assert_eq!(*register.value(), "b");
+        assert_eq!(register.timestamp(), 7);
+    }
+}
@@ -0,0 +1,281 @@
+//! Operation-based add-only monotonic DAG
+//!
+//! Vertices sit between the sentinels `⊢` and `⊣`. Edges may only be added
+//! along an existing path, so concurrent inserts can never close a cycle.
+//!
+//! ```txt
+//! payload set V, set E // V: vertices; E: edges
+//!   initial {⊢, ⊣}, {(⊢, ⊣)}
+//! query lookup (vertex v) : boolean b
+//!   let b = (v ∈ V)
+//! query before (vertex u, v) : boolean b
+//!   pre lookup(u) ∧ lookup(v)
+//!   let b = (∃w1, ..., wm ∈ V : w1 = u ∧ wm = v ∧ ∀j : (wj, wj+1) ∈ E)
+//! update addEdge (vertex u, v)
+//!   atSource (u, v)
+//!     pre lookup(u) ∧ lookup(v)
+//!     pre before(u, v) // Only allow edges that preserve the order
+//!   downstream (u, v)
+//!     E := E ∪ {(u, v)}
+//! update addBetween (vertex u, v, w)
+//!   atSource (u, v, w)
+//!     pre lookup(u) ∧ lookup(w)
+//!     pre before(u, w)
+//!     pre v ∉ V // v is fresh
+//!   downstream (u, v, w)
+//!     V := V ∪ {v}
+//!     E := E ∪ {(u, v), (v, w)}
+//! ```
+
+use std::{collections::BTreeSet, error, fmt};
+
+use super::OpsBased;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonotonicDagError {
+    VertexNotFound,
+    VertexExists,
+    /// The edge would close a cycle.
+    WouldCreateCycle,
+    /// The vertices are not ordered yet, so the edge would add a new order.
+    Unordered,
+}
+
+impl fmt::Display for MonotonicDagError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::VertexNotFound => write!(f, "vertex is not present"),
+            Self::VertexExists => write!(f, "vertex is already present"),
+            Self::WouldCreateCycle => write!(f, "edge would create a cycle"),
+            Self::Unordered => write!(f, "vertices are not ordered by an existing path"),
+        }
+    }
+}
+
+impl error::Error for MonotonicDagError {}
+
+/// `Start` and `End` are the sentinels `⊢` and `⊣`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Vertex<T> {
+    Start,
+    Inner(T),
+    End,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonotonicDagOp<T> {
+    AddEdge(Vertex<T>, Vertex<T>),
+    AddBetween(Vertex<T>, T, Vertex<T>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonotonicDag<T> {
+    vertices: BTreeSet<Vertex<T>>,
+    edges: BTreeSet<(Vertex<T>, Vertex<T>)>,
+}
+
+impl<T> Default for MonotonicDag<T>
+where
+    T: Ord,
+{
+    fn default() -> Self {
+        Self {
+            vertices: BTreeSet::from([Vertex::Start, Vertex::End]),
+            edges: BTreeSet::from([(Vertex::Start, Vertex::End)]),
+        }
+    }
+}
+
+/// Whether a non-empty path leads from `from` to `to` along `edges`.
+pub(super) fn path<T: Ord>(
+    edges: &BTreeSet<(Vertex<T>, Vertex<T>)>,
+    from: &Vertex<T>,
+    to: &Vertex<T>,
+) -> bool {
+    let mut visited = BTreeSet::new();
+    let mut stack = vec![from];
+    while let Some(vertex) = stack.pop() {
+        for (_, next) in edges.iter().filter(|(source, _)| source == vertex) {
+            if next == to {
+                return true;
+            }
+            if visited.insert(next) {
+                stack.push(next);
+            }
+        }
+    }
+    false
+}
+
+impl<T> MonotonicDag<T>
+where
+    T: Ord + Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lookup(&self, vertex: &Vertex<T>) -> bool {
+        self.vertices.contains(vertex)
+    }
+
+    pub fn before(&self, u: &Vertex<T>, v: &Vertex<T>) -> Result<bool, MonotonicDagError> {
+        if !self.lookup(u) || !self.lookup(v) {
+            return Err(MonotonicDagError::VertexNotFound);
+        }
+        Ok(path(&self.edges, u, v))
+    }
+
+    /// Checks that an edge from `u` to `v` follows the existing order.
+    fn check_order(&self, u: &Vertex<T>, v: &Vertex<T>) -> Result<(), MonotonicDagError> {
+        if self.before(u, v)? {
+            Ok(())
+        } else if u == v || self.before(v, u)? {
+            Err(MonotonicDagError::WouldCreateCycle)
+        } else {
+            Err(MonotonicDagError::Unordered)
+        }
+    }
+
+    /// Neither update returns anything at the source.
+    pub fn at_source(&mut self, _op: &MonotonicDagOp<T>) -> Option<MonotonicDag<T>> {
+        None
+    }
+
+    pub fn downstream(&mut self, op: &MonotonicDagOp<T>) {
+        match op {
+            MonotonicDagOp::AddEdge(u, v) => {
+                self.edges.insert((u.clone(), v.clone()));
+            }
+            MonotonicDagOp::AddBetween(u, v, w) => {
+                let v = Vertex::Inner(v.clone());
+                self.vertices.insert(v.clone());
+                self.edges.insert((u.clone(), v.clone()));
+                self.edges.insert((v, w.clone()));
+            }
+        }
+    }
+}
+
+impl<T> OpsBased<MonotonicDag<T>> for MonotonicDag<T>
+where
+    T: Ord + Clone,
+{
+    type Query = fn(&MonotonicDag<T>) -> Option<MonotonicDag<T>>;
+    type Args = MonotonicDagOp<T>;
+    type AtSource = fn(&mut MonotonicDag<T>, &Self::Args) -> Option<MonotonicDag<T>>;
+    type Downstream = fn(&mut MonotonicDag<T>, &Self::Args);
+    type Error = MonotonicDagError;
+
+    fn query(&self, query: Self::Query) -> Result<Option<MonotonicDag<T>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(
+        &mut self,
+        args: &Self::Args,
+        at_source: Self::AtSource,
+        downstream: Self::Downstream,
+    ) -> Result<Option<MonotonicDag<T>>, Self::Error> {
+        match args {
+            MonotonicDagOp::AddEdge(u, v) => self.check_order(u, v)?,
+            MonotonicDagOp::AddBetween(u, v, w) => {
+                self.check_order(u, w)?;
+                if self.lookup(&Vertex::Inner(v.clone())) {
+                    return Err(MonotonicDagError::VertexExists);
+                }
+            }
+        }
+        let res = at_source(self, args);
+        downstream(self, args);
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops_based::Payload;
+
+    fn update(
+        payload: &mut Payload<MonotonicDag<char>>,
+        op: MonotonicDagOp<char>,
+    ) -> Result<(), MonotonicDagError> {
+        payload
+            .update(&op, MonotonicDag::at_source, MonotonicDag::downstream)
+            .map(|_| ())
+    }
+
+    #[test]
+    fn test_update() {
+        let mut payload = Payload::new(MonotonicDag::new());
+        let op = MonotonicDagOp::AddBetween(Vertex::Start, 'a', Vertex::End);
+        update(&mut payload, op).unwrap();
+        let op = MonotonicDagOp::AddBetween(Vertex::Inner('a'), 'b', Vertex::End);
+        update(&mut payload, op).unwrap();
+        let op = MonotonicDagOp::AddEdge(Vertex::Start, Vertex::Inner('b'));
+        update(&mut payload, op).unwrap();
+
+        let dag = payload.query(|dag| Some(dag.clone())).unwrap().unwrap();
+        assert!(dag.lookup(&Vertex::Inner('b')));
+        assert_eq!(
+            dag.before(&Vertex::Inner('a'), &Vertex::Inner('b')),
+            Ok(true)
+        );
+        assert_eq!(
+            dag.before(&Vertex::Inner('b'), &Vertex::Inner('a')),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn test_update_preconditions() {
+        let mut payload = Payload::new(MonotonicDag::new());
+        let op = MonotonicDagOp::AddBetween(Vertex::Start, 'a', Vertex::End);
+        update(&mut payload, op.clone()).unwrap();
+        assert_eq!(
+            update(&mut payload, op),
+            Err(MonotonicDagError::VertexExists)
+        );
+
+        let op = MonotonicDagOp::AddBetween(Vertex::Start, 'b', Vertex::End);
+        update(&mut payload, op).unwrap();
+        assert_eq!(
+            update(
+                &mut payload,
+                MonotonicDagOp::AddEdge(Vertex::End, Vertex::Inner('a'))
+            ),
+            Err(MonotonicDagError::WouldCreateCycle)
+        );
+        assert_eq!(
+            update(
+                &mut payload,
+                MonotonicDagOp::AddEdge(Vertex::Inner('a'), Vertex::Inner('b'))
+            ),
+            Err(MonotonicDagError::Unordered)
+        );
+        assert_eq!(
+            update(
+                &mut payload,
+                MonotonicDagOp::AddBetween(Vertex::Inner('c'), 'd', Vertex::End)
+            ),
+            Err(MonotonicDagError::VertexNotFound)
+        );
+    }
+
+    #[test]
+    fn test_downstream_commutes() {
+        let ops = [
+            MonotonicDagOp::AddBetween(Vertex::Start, 'a', Vertex::End),
+            MonotonicDagOp::AddBetween(Vertex::Start, 'b', Vertex::End),
+            MonotonicDagOp::AddBetween(Vertex::Inner('a'), 'c', Vertex::End),
+        ];
+        let mut replica1 = MonotonicDag::new();
+        let mut replica2 = MonotonicDag::new();
+        ops.iter().for_each(|op| replica1.downstream(op));
+        [&ops[1], &ops[0], &ops[2]]
+            .into_iter()
+            .for_each(|op| replica2.downstream(op));
+        assert_eq!(replica1, replica2);
+    }
+}
@@ -0,0 +1,222 @@
+//! Operation-based observed-remove set (OR-Set)
+//!
+//! Every add tags the element with a unique tag, and a remove only cancels
+//! the tags it observed at its source. An add concurrent with a remove
+//! therefore wins. Tags are dots drawn from a version vector of the adds
+//! delivered so far, so an add must be applied at its source before the next
+//! one is prepared there.
+//!
+//! ```txt
+//! payload set S // set of pairs { (element e, unique-tag u), ... }
+//!   initial ∅
+//! query lookup (element e) : boolean b
+//!   let b = (∃u : (e, u) ∈ S)
+//! update add (element e)
+//!   atSource (e)
+//!     let α = unique() // unique() returns a unique value
+//!   downstream (e, α)
+//!     S := S ∪ {(e, α)}
+//! update remove (element e)
+//!   atSource (e)
+//!     pre lookup(e)
+//!     let R = {(e, u) | ∃u : (e, u) ∈ S}
+//!   downstream (R)
+//!     pre ∀(e, u) ∈ R : add(e, u) has been delivered // U-Set precondition
+//!     S := S \ R // Downstream: remove pairs observed at source
+//! ```
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error, fmt,
+};
+
+use super::OpsBased;
+use crate::{
+    state_based::{Dot, VersionVector},
+    ReplicaId,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ORSetError {
+    NotFound,
+}
+
+impl fmt::Display for ORSetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "element is not present"),
+        }
+    }
+}
+
+impl error::Error for ORSetError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ORSetOp<T> {
+    Add { element: T, tag: Dot },
+    Remove { element: T, tags: BTreeSet<Dot> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ORSet<T> {
+    s: BTreeMap<T, BTreeSet<Dot>>,
+    delivered: VersionVector,
+}
+
+impl<T> Default for ORSet<T> {
+    fn default() -> Self {
+        Self {
+            s: BTreeMap::new(),
+            delivered: VersionVector::new(),
+        }
+    }
+}
+
+impl<T> ORSet<T>
+where
+    T: Ord + Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lookup(&self, element: &T) -> bool {
+        self.s.contains_key(element)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.s.keys()
+    }
+
+    /// Tags `element` with a fresh unique tag.
+    pub fn add_op(&self, replica: ReplicaId, element: T) -> ORSetOp<T> {
+        ORSetOp::Add {
+            element,
+            tag: self.delivered.next_dot(replica),
+        }
+    }
+
+    /// Collects the tags of `element` observed at this replica.
+    pub fn remove_op(&self, element: T) -> ORSetOp<T> {
+        let tags = self.s.get(&element).cloned().unwrap_or_default();
+        ORSetOp::Remove { element, tags }
+    }
+
+    /// Neither update returns anything at the source.
+    pub fn at_source(&mut self, _op: &ORSetOp<T>) -> Option<ORSet<T>> {
+        None
+    }
+
+    pub fn downstream(&mut self, op: &ORSetOp<T>) {
+        match op {
+            ORSetOp::Add { element, tag } => {
+                self.s.entry(element.clone()).or_default().insert(*tag);
+                self.delivered.witness(*tag);
+            }
+            ORSetOp::Remove { element, tags } => {
+                if let Some(observed) = self.s.get_mut(element) {
+                    observed.retain(|tag| !tags.contains(tag));
+                    if observed.is_empty() {
+                        self.s.remove(element);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T> OpsBased<ORSet<T>> for ORSet<T>
+where
+    T: Ord + Clone,
+{
+    type Query = fn(&ORSet<T>) -> Option<ORSet<T>>;
+    type Args = ORSetOp<T>;
+    type AtSource = fn(&mut ORSet<T>, &Self::Args) -> Option<ORSet<T>>;
+    type Downstream = fn(&mut ORSet<T>, &Self::Args);
+    type Error = ORSetError;
+
+    fn query(&self, query: Self::Query) -> Result<Option<ORSet<T>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(
+        &mut self,
+        args: &Self::Args,
+        at_source: Self::AtSource,
+        downstream: Self::Downstream,
+    ) -> Result<Option<ORSet<T>>, Self::Error> {
+        if let ORSetOp::Remove { element, .. } = args {
+            if !self.lookup(element) {
+                return Err(ORSetError::NotFound);
+            }
+        }
+        let res = at_source(self, args);
+        downstream(self, args);
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops_based::Payload;
+
+    #[test]
+    fn test_update() {
+        let mut payload = Payload::new(ORSet::new());
+        let op = payload
+            .query(|set| Some(set.clone()))
+            .unwrap()
+            .unwrap()
+            .add_op(0, "a");
+        payload
+            .update(&op, ORSet::at_source, ORSet::downstream)
+            .unwrap();
+        assert_eq!(
+            payload.update(
+                &ORSetOp::Remove {
+                    element: "b",
+                    tags: BTreeSet::new(),
+                },
+                ORSet::at_source,
+                ORSet::downstream,
+            ),
+            Err(ORSetError::NotFound)
+        );
+
+        let set = payload.query(|set| Some(set.clone())).unwrap().unwrap();
+        assert!(set.lookup(&"a"));
+        assert!(!set.lookup(&"b"));
+    }
+
+    #[test]
+    fn test_remove_observed() {
+        let mut set = ORSet::new();
+        let add = set.add_op(0, "a");
+        set.downstream(&add);
+        let add_again = set.add_op(0, "a");
+        set.downstream(&add_again);
+        let remove = set.remove_op("a");
+        set.downstream(&remove);
+        assert!(!set.lookup(&"a"));
+    }
+
+    #[test]
+    fn test_concurrent_add_wins() {
+        let mut replica1 = ORSet::new();
+        let add = replica1.add_op(0, "a");
+        replica1.downstream(&add);
+        let mut replica2 = replica1.clone();
+
+        // Replica 1 removes "a" while replica 2 adds it again.
+        let remove = replica1.remove_op("a");
+        replica1.downstream(&remove);
+        let add_again = replica2.add_op(1, "a");
+        replica2.downstream(&add_again);
+
+        replica1.downstream(&add_again);
+        replica2.downstream(&remove);
+        assert_eq!(replica1, replica2);
+        assert!(replica1.lookup(&"a"));
+    }
+}
@@ -0,0 +1,323 @@
+//! Operation-based replicated growable array (RGA)
+//!
+//! A sequence stored as a linked list of timestamped vertices. An insert
+//! skips the successors of its anchor that carry a greater timestamp, which
+//! orders concurrent inserts at the same place identically everywhere.
+//! Removed vertices stay as tombstones so they can still anchor inserts.
+//! Timestamps are Lamport clocks: greater than every timestamp delivered so
+//! far, with ties broken by replica.
+//!
+//! ```txt
+//! payload set N, set R // N: list of (atom, timestamp) vertices; R: removed
+//!   initial ⊢ ⊣, ∅
+//! query lookup (vertex v) : boolean b
+//!   let b = (v ∈ N \ R)
+//! update addRight (vertex u, atom a)
+//!   atSource (u, a) : w
+//!     pre lookup(u) ∨ u = ⊢
+//!     let t = now()
+//!     let w = (a, t)
+//!   downstream (u, w)
+//!     pre u has been delivered
+//!     let l = u; let r = successor(u)
+//!     while r ≠ ⊣ ∧ r.t > w.t do l, r := r, successor(r)
+//!     N := insert w between l and r
+//! update remove (vertex w)
+//!   atSource (w)
+//!     pre lookup(w)
+//!   downstream (w)
+//!     pre addRight(_, w) has been delivered
+//!     R := R ∪ {w}
+//! ```
+
+use std::{error, fmt};
+
+use super::OpsBased;
+use crate::ReplicaId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RgaError {
+    IndexOutOfBounds,
+    VertexNotFound,
+}
+
+impl fmt::Display for RgaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IndexOutOfBounds => write!(f, "position is out of bounds"),
+            Self::VertexNotFound => write!(f, "vertex is not present"),
+        }
+    }
+}
+
+impl error::Error for RgaError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RgaTimestamp {
+    pub counter: u64,
+    pub replica: ReplicaId,
+}
+
+/// `after` is `None` for the head `⊢`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RgaOp<T> {
+    AddRight {
+        after: Option<RgaTimestamp>,
+        value: T,
+        timestamp: RgaTimestamp,
+    },
+    Remove(RgaTimestamp),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Vertex<T> {
+    value: T,
+    timestamp: RgaTimestamp,
+    removed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rga<T> {
+    vertices: Vec<Vertex<T>>,
+    clock: u64,
+}
+
+impl<T> Default for Rga<T> {
+    fn default() -> Self {
+        Self {
+            vertices: Vec::new(),
+            clock: 0,
+        }
+    }
+}
+
+impl<T> Rga<T>
+where
+    T: Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lookup(&self, timestamp: &RgaTimestamp) -> bool {
+        self.index_of(timestamp)
+            .is_some_and(|index| !self.vertices[index].removed)
+    }
+
+    /// The values in sequence order, skipping tombstones.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.visible().map(|vertex| &vertex.value)
+    }
+
+    /// Prepares inserting `value` right after the `after`-th visible value,
+    /// or at the front when `after` is `None`. The timestamp is drawn from
+    /// the ops delivered so far, so an insert must be applied at its source
+    /// before the next one is prepared there.
+    pub fn add_right_op(
+        &self,
+        replica: ReplicaId,
+        after: Option<usize>,
+        value: T,
+    ) -> Result<RgaOp<T>, RgaError> {
+        let after = match after {
+            Some(position) => Some(self.timestamp_at(position)?),
+            None => None,
+        };
+        let timestamp = RgaTimestamp {
+            counter: self.clock + 1,
+            replica,
+        };
+        Ok(RgaOp::AddRight {
+            after,
+            value,
+            timestamp,
+        })
+    }
+
+    /// Prepares removing the `position`-th visible value.
+    pub fn remove_op(&self, position: usize) -> Result<RgaOp<T>, RgaError> {
+        Ok(RgaOp::Remove(self.timestamp_at(position)?))
+    }
+
+    /// Neither update returns anything at the source.
+    pub fn at_source(&mut self, _op: &RgaOp<T>) -> Option<Rga<T>> {
+        None
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the vertex the op refers to has not been delivered yet, i.e.
+    /// if ops are not delivered in causal order.
+    pub fn downstream(&mut self, op: &RgaOp<T>) {
+        match op {
+            RgaOp::AddRight {
+                after,
+                value,
+                timestamp,
+            } => {
+                let mut index = match after {
+                    Some(after) => self.index_of(after).expect("anchor not delivered") + 1,
+                    None => 0,
+                };
+                while index < self.vertices.len() && self.vertices[index].timestamp > *timestamp {
+                    index += 1;
+                }
+                self.vertices.insert(
+                    index,
+                    Vertex {
+                        value: value.clone(),
+                        timestamp: *timestamp,
+                        removed: false,
+                    },
+                );
+                self.clock = self.clock.max(timestamp.counter);
+            }
+            RgaOp::Remove(timestamp) => {
+                let index = self.index_of(timestamp).expect("vertex not delivered");
+                self.vertices[index].removed = true;
+            }
+        }
+    }
+
+    fn visible(&self) -> impl Iterator<Item = &Vertex<T>> {
+        self.vertices.iter().filter(|vertex| !vertex.removed)
+    }
+
+    fn index_of(&self, timestamp: &RgaTimestamp) -> Option<usize> {
+        self.vertices
+            .iter()
+            .position(|vertex| vertex.timestamp == *timestamp)
+    }
+
+    fn timestamp_at(&self, position: usize) -> Result<RgaTimestamp, RgaError> {
+        self.visible()
+            .nth(position)
+            .map(|vertex| vertex.timestamp)
+            .ok_or(RgaError::IndexOutOfBounds)
+    }
+}
+
+impl<T> OpsBased<Rga<T>> for Rga<T>
+where
+    T: Clone,
+{
+    type Query = fn(&Rga<T>) -> Option<Rga<T>>;
+    type Args = RgaOp<T>;
+    type AtSource = fn(&mut Rga<T>, &Self::Args) -> Option<Rga<T>>;
+    type Downstream = fn(&mut Rga<T>, &Self::Args);
+    type Error = RgaError;
+
+    fn query(&self, query: Self::Query) -> Result<Option<Rga<T>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(
+        &mut self,
+        args: &Self::Args,
+        at_source: Self::AtSource,
+        downstream: Self::Downstream,
+    ) -> Result<Option<Rga<T>>, Self::Error> {
+        match args {
+            RgaOp::AddRight {
+                after: Some(after), ..
+            }
+            | RgaOp::Remove(after) => {
+                if !self.lookup(after) {
+                    return Err(RgaError::VertexNotFound);
+                }
+            }
+            RgaOp::AddRight { after: None, .. } => {}
+        }
+        let res = at_source(self, args);
+        downstream(self, args);
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops_based::Payload;
+
+    fn text(rga: &Rga<char>) -> String {
+        rga.iter().collect()
+    }
+
+    #[test]
+    fn test_update() {
+        let mut payload = Payload::new(Rga::new());
+        let snapshot =
+            |payload: &Payload<Rga<char>>| payload.query(|rga| Some(rga.clone())).unwrap().unwrap();
+        for (after, value) in [(None, 'a'), (Some(0), 'c'), (Some(0), 'b')] {
+            let op = snapshot(&payload).add_right_op(0, after, value).unwrap();
+            payload
+                .update(&op, Rga::at_source, Rga::downstream)
+                .unwrap();
+        }
+        assert_eq!(text(&snapshot(&payload)), "abc");
+
+        let op = snapshot(&payload).remove_op(1).unwrap();
+        payload
+            .update(&op, Rga::at_source, Rga::downstream)
+            .unwrap();
+        assert_eq!(text(&snapshot(&payload)), "ac");
+        assert_eq!(
+            payload.update(&op, Rga::at_source, Rga::downstream),
+            Err(RgaError::VertexNotFound)
+        );
+        assert_eq!(
+            snapshot(&payload).remove_op(2),
+            Err(RgaError::IndexOutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_concurrent_inserts_converge() {
+        let mut replica1 = Rga::new();
+        let op = replica1.add_right_op(0, None, 'a').unwrap();
+        replica1.downstream(&op);
+        let mut replica2 = replica1.clone();
+
+        let op1 = replica1.add_right_op(1, Some(0), 'b').unwrap();
+        replica1.downstream(&op1);
+        let op2 = replica2.add_right_op(2, Some(0), 'c').unwrap();
+        replica2.downstream(&op2);
+        let op3 = replica2.add_right_op(2, Some(1), 'd').unwrap();
+        replica2.downstream(&op3);
+
+        replica1.downstream(&op2);
+        replica1.downstream(&op3);
+        replica2.downstream(&op1);
+        assert_eq!(replica1, replica2);
+        assert_eq!(text(&replica1), "acdb");
+    }
+
+    #[test]
+    fn test_insert_after_concurrently_removed() {
+        let mut replica1 = Rga::new();
+        let op = replica1.add_right_op(0, None, 'a').unwrap();
+        replica1.downstream(&op);
+        let mut replica2 = replica1.clone();
+
+        let remove = replica1.remove_op(0).unwrap();
+        replica1.downstream(&remove);
+        let insert = replica2.add_right_op(1, Some(0), 'b').unwrap();
+        replica2.downstream(&insert);
+
+        replica1.downstream(&insert);
+        replica2.downstream(&remove);
+        assert_eq!(replica1, replica2);
+        assert_eq!(text(&replica1), "b");
+    }
+
+    #[test]
+    #[should_panic(expected = "anchor not delivered")]
+    fn test_downstream_unknown_anchor() {
+        let mut replica1 = Rga::new();
+        let first = replica1.add_right_op(0, None, 'a').unwrap();
+        replica1.downstream(&first);
+        let second = replica1.add_right_op(0, Some(0), 'b').unwrap();
+
+        Rga::new().downstream(&second);
+    }
+}
@@ -0,0 +1,189 @@
+//! Operation-based two-phase set (2P-Set)
+//!
+//! An element may be added and removed, but never added again once removed.
+//!
+//! ```txt
+//! payload set A, set R // A: added; R: removed
+//!   initial ∅, ∅
+//! query lookup (element e) : boolean b
+//!   let b = (e ∈ A ∧ e ∉ R)
+//! update add (element e)
+//!   atSource (e)
+//!   downstream (e)
+//!     A := A ∪ {e}
+//! update remove (element e)
+//!   atSource (e)
+//!     pre lookup(e)
+//!   downstream (e)
+//!     pre add(e) has been delivered // Causal order suffices
+//!     R := R ∪ {e}
+//! ```
+
+use std::{collections::BTreeSet, error, fmt};
+
+use super::OpsBased;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TwoPSetError {
+    NotAdded,
+    AlreadyRemoved,
+}
+
+impl fmt::Display for TwoPSetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAdded => write!(f, "element was never added"),
+            Self::AlreadyRemoved => write!(f, "element was already removed"),
+        }
+    }
+}
+
+impl error::Error for TwoPSetError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TwoPSetOp<T> {
+    Add(T),
+    Remove(T),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TwoPSet<T> {
+    a: BTreeSet<T>,
+    r: BTreeSet<T>,
+}
+
+impl<T> Default for TwoPSet<T> {
+    fn default() -> Self {
+        Self {
+            a: BTreeSet::new(),
+            r: BTreeSet::new(),
+        }
+    }
+}
+
+impl<T> TwoPSet<T>
+where
+    T: Ord + Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lookup(&self, element: &T) -> bool {
+        self.a.contains(element) && !self.r.contains(element)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.a.difference(&self.r)
+    }
+
+    /// Neither update returns anything at the source.
+    pub fn at_source(&mut self, _op: &TwoPSetOp<T>) -> Option<TwoPSet<T>> {
+        None
+    }
+
+    /// # Panics
+    ///
+    /// Panics if a remove is delivered before the add of its element, i.e. if
+    /// ops are not delivered in causal order.
+    pub fn downstream(&mut self, op: &TwoPSetOp<T>) {
+        match op {
+            TwoPSetOp::Add(element) => self.a.insert(element.clone()),
+            TwoPSetOp::Remove(element) => {
+                assert!(self.a.contains(element), "add not delivered");
+                self.r.insert(element.clone())
+            }
+        };
+    }
+}
+
+impl<T> OpsBased<TwoPSet<T>> for TwoPSet<T>
+where
+    T: Ord + Clone,
+{
+    type Query = fn(&TwoPSet<T>) -> Option<TwoPSet<T>>;
+    type Args = TwoPSetOp<T>;
+    type AtSource = fn(&mut TwoPSet<T>, &Self::Args) -> Option<TwoPSet<T>>;
+    type Downstream = fn(&mut TwoPSet<T>, &Self::Args);
+    type Error = TwoPSetError;
+
+    fn query(&self, query: Self::Query) -> Result<Option<TwoPSet<T>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(
+        &mut self,
+        args: &Self::Args,
+        at_source: Self::AtSource,
+        downstream: Self::Downstream,
+    ) -> Result<Option<TwoPSet<T>>, Self::Error> {
+        if let TwoPSetOp::Remove(element) = args {
+            if !self.a.contains(element) {
+                return Err(TwoPSetError::NotAdded);
+            }
+            if self.r.contains(element) {
+                return Err(TwoPSetError::AlreadyRemoved);
+            }
+        }
+        let res = at_source(self, args);
+        downstream(self, args);
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops_based::Payload;
+
+    fn update(payload: &mut Payload<TwoPSet<i32>>, op: TwoPSetOp<i32>) -> Result<(), TwoPSetError> {
+        payload
+            .update(&op, TwoPSet::at_source, TwoPSet::downstream)
+            .map(|_| ())
+    }
+
+    #[test]
+    fn test_update() {
+        let mut payload = Payload::new(TwoPSet::new());
+        update(&mut payload, TwoPSetOp::Add(1)).unwrap();
+        update(&mut payload, TwoPSetOp::Add(2)).unwrap();
+        update(&mut payload, TwoPSetOp::Remove(1)).unwrap();
+
+        let set = payload.query(|set| Some(set.clone())).unwrap().unwrap();
+        assert!(!set.lookup(&1));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![&2]);
+    }
+
+    #[test]
+    fn test_update_remove_precondition() {
+        let mut payload = Payload::new(TwoPSet::new());
+        assert_eq!(
+            update(&mut payload, TwoPSetOp::Remove(1)),
+            Err(TwoPSetError::NotAdded)
+        );
+        update(&mut payload, TwoPSetOp::Add(1)).unwrap();
+        update(&mut payload, TwoPSetOp::Remove(1)).unwrap();
+        assert_eq!(
+            update(&mut payload, TwoPSetOp::Remove(1)),
+            Err(TwoPSetError::AlreadyRemoved)
+        );
+    }
+
+    #[test]
+    fn test_downstream_commutes() {
+        let ops = [TwoPSetOp::Add(1), TwoPSetOp::Add(2), TwoPSetOp::Remove(1)];
+        let mut replica1 = TwoPSet::new();
+        let mut replica2 = TwoPSet::new();
+        ops.iter().for_each(|op| replica1.downstream(op));
+        [&ops[1], &ops[0], &ops[2]]
+            .into_iter()
+            .for_each(|op| replica2.downstream(op));
+        assert_eq!(replica1, replica2);
+    }
+
+    #[test]
+    #[should_panic(expected = "add not delivered")]
+    fn test_downstream_remove_before_add() {
+        TwoPSet::new().downstream(&TwoPSetOp::Remove(1));
+    }
+}
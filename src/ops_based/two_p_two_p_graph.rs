@@ -0,0 +1,256 @@
+//! Operation-based 2P2P-Graph
+//!
+//! A directed graph whose vertices and edges are each a [`TwoPSet`]. The
+//! preconditions at the source keep every edge between two present vertices.
+//!
+//! ```txt
+//! payload set VA, VR, EA, ER // V: vertices; E: edges; A: added; R: removed
+//!   initial ∅, ∅, ∅, ∅
+//! query lookup (vertex v) : boolean b
+//!   let b = (v ∈ (VA \ VR))
+//! query lookup (edge (u, v)) : boolean b
+//!   let b = (lookup(u) ∧ lookup(v) ∧ (u, v) ∈ (EA \ ER))
+//! update addVertex (vertex w)
+//!   atSource (w)
+//!   downstream (w)
+//!     VA := VA ∪ {w}
+//! update addEdge (vertex u, vertex v)
+//!   atSource (u, v)
+//!     pre lookup(u) ∧ lookup(v) // Graph precondition: E ⊆ V × V
+//!   downstream (u, v)
+//!     EA := EA ∪ {(u, v)}
+//! update removeVertex (vertex w)
+//!   atSource (w)
+//!     pre lookup(w) // 2P-Set precondition
+//!     pre ∀(u, v) ∈ (EA \ ER) : u ≠ w ∧ v ≠ w // Graph precondition: E ⊆ V × V
+//!   downstream (w)
+//!     pre addVertex(w) delivered // 2P-Set precondition
+//!     VR := VR ∪ {w}
+//! update removeEdge (edge (u, v))
+//!   atSource ((u, v))
+//!     pre lookup((u, v)) // 2P-Set precondition
+//!   downstream ((u, v))
+//!     pre addEdge(u, v) delivered // 2P-Set precondition
+//!     ER := ER ∪ {(u, v)}
+//! ```
+
+use std::{error, fmt};
+
+use super::{OpsBased, TwoPSet, TwoPSetOp};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TwoPTwoPGraphError {
+    VertexNotFound,
+    EdgeNotFound,
+    VertexHasEdges,
+}
+
+impl fmt::Display for TwoPTwoPGraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::VertexNotFound => write!(f, "vertex is not present"),
+            Self::EdgeNotFound => write!(f, "edge is not present"),
+            Self::VertexHasEdges => write!(f, "vertex still has incident edges"),
+        }
+    }
+}
+
+impl error::Error for TwoPTwoPGraphError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TwoPTwoPGraphOp<V> {
+    AddVertex(V),
+    AddEdge(V, V),
+    RemoveVertex(V),
+    RemoveEdge(V, V),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TwoPTwoPGraph<V> {
+    vertices: TwoPSet<V>,
+    edges: TwoPSet<(V, V)>,
+}
+
+impl<V> Default for TwoPTwoPGraph<V> {
+    fn default() -> Self {
+        Self {
+            vertices: TwoPSet::default(),
+            edges: TwoPSet::default(),
+        }
+    }
+}
+
+impl<V> TwoPTwoPGraph<V>
+where
+    V: Ord + Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lookup_vertex(&self, vertex: &V) -> bool {
+        self.vertices.lookup(vertex)
+    }
+
+    pub fn lookup_edge(&self, from: &V, to: &V) -> bool {
+        self.lookup_vertex(from)
+            && self.lookup_vertex(to)
+            && self.edges.lookup(&(from.clone(), to.clone()))
+    }
+
+    pub fn vertices(&self) -> impl Iterator<Item = &V> {
+        self.vertices.iter()
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (&V, &V)> {
+        self.edges
+            .iter()
+            .filter(|(from, to)| self.lookup_vertex(from) && self.lookup_vertex(to))
+            .map(|(from, to)| (from, to))
+    }
+
+    /// None of the updates return anything at the source.
+    pub fn at_source(&mut self, _op: &TwoPTwoPGraphOp<V>) -> Option<TwoPTwoPGraph<V>> {
+        None
+    }
+
+    pub fn downstream(&mut self, op: &TwoPTwoPGraphOp<V>) {
+        match op {
+            TwoPTwoPGraphOp::AddVertex(vertex) => {
+                self.vertices.downstream(&TwoPSetOp::Add(vertex.clone()))
+            }
+            TwoPTwoPGraphOp::AddEdge(from, to) => self
+                .edges
+                .downstream(&TwoPSetOp::Add((from.clone(), to.clone()))),
+            TwoPTwoPGraphOp::RemoveVertex(vertex) => {
+                self.vertices.downstream(&TwoPSetOp::Remove(vertex.clone()))
+            }
+            TwoPTwoPGraphOp::RemoveEdge(from, to) => self
+                .edges
+                .downstream(&TwoPSetOp::Remove((from.clone(), to.clone()))),
+        }
+    }
+}
+
+impl<V> OpsBased<TwoPTwoPGraph<V>> for TwoPTwoPGraph<V>
+where
+    V: Ord + Clone,
+{
+    type Query = fn(&TwoPTwoPGraph<V>) -> Option<TwoPTwoPGraph<V>>;
+    type Args = TwoPTwoPGraphOp<V>;
+    type AtSource = fn(&mut TwoPTwoPGraph<V>, &Self::Args) -> Option<TwoPTwoPGraph<V>>;
+    type Downstream = fn(&mut TwoPTwoPGraph<V>, &Self::Args);
+    type Error = TwoPTwoPGraphError;
+
+    fn query(&self, query: Self::Query) -> Result<Option<TwoPTwoPGraph<V>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(
+        &mut self,
+        args: &Self::Args,
+        at_source: Self::AtSource,
+        downstream: Self::Downstream,
+    ) -> Result<Option<TwoPTwoPGraph<V>>, Self::Error> {
+        match args {
+            TwoPTwoPGraphOp::AddVertex(_) => {}
+            TwoPTwoPGraphOp::AddEdge(from, to) => {
+                if !self.lookup_vertex(from) || !self.lookup_vertex(to) {
+                    return Err(TwoPTwoPGraphError::VertexNotFound);
+                }
+            }
+            TwoPTwoPGraphOp::RemoveVertex(vertex) => {
+                if !self.lookup_vertex(vertex) {
+                    return Err(TwoPTwoPGraphError::VertexNotFound);
+                }
+                if self
+                    .edges()
+                    .any(|(from, to)| from == vertex || to == vertex)
+                {
+                    return Err(TwoPTwoPGraphError::VertexHasEdges);
+                }
+            }
+            TwoPTwoPGraphOp::RemoveEdge(from, to) => {
+                if !self.lookup_edge(from, to) {
+                    return Err(TwoPTwoPGraphError::EdgeNotFound);
+                }
+            }
+        }
+        let res = at_source(self, args);
+        downstream(self, args);
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops_based::Payload;
+
+    fn update(
+        payload: &mut Payload<TwoPTwoPGraph<u32>>,
+        op: TwoPTwoPGraphOp<u32>,
+    ) -> Result<(), TwoPTwoPGraphError> {
+        payload
+            .update(&op, TwoPTwoPGraph::at_source, TwoPTwoPGraph::downstream)
+            .map(|_| ())
+    }
+
+    #[test]
+    fn test_update() {
+        let mut payload = Payload::new(TwoPTwoPGraph::new());
+        update(&mut payload, TwoPTwoPGraphOp::AddVertex(1)).unwrap();
+        update(&mut payload, TwoPTwoPGraphOp::AddVertex(2)).unwrap();
+        update(&mut payload, TwoPTwoPGraphOp::AddEdge(1, 2)).unwrap();
+
+        let graph = payload.query(|graph| Some(graph.clone())).unwrap().unwrap();
+        assert!(graph.lookup_edge(&1, &2));
+        assert!(!graph.lookup_edge(&2, &1));
+        assert_eq!(graph.edges().collect::<Vec<_>>(), vec![(&1, &2)]);
+
+        update(&mut payload, TwoPTwoPGraphOp::RemoveEdge(1, 2)).unwrap();
+        update(&mut payload, TwoPTwoPGraphOp::RemoveVertex(2)).unwrap();
+        let graph = payload.query(|graph| Some(graph.clone())).unwrap().unwrap();
+        assert_eq!(graph.vertices().collect::<Vec<_>>(), vec![&1]);
+    }
+
+    #[test]
+    fn test_update_preconditions() {
+        let mut payload = Payload::new(TwoPTwoPGraph::new());
+        update(&mut payload, TwoPTwoPGraphOp::AddVertex(1)).unwrap();
+        assert_eq!(
+            update(&mut payload, TwoPTwoPGraphOp::AddEdge(1, 2)),
+            Err(TwoPTwoPGraphError::VertexNotFound)
+        );
+        update(&mut payload, TwoPTwoPGraphOp::AddVertex(2)).unwrap();
+        update(&mut payload, TwoPTwoPGraphOp::AddEdge(1, 2)).unwrap();
+        assert_eq!(
+            update(&mut payload, TwoPTwoPGraphOp::RemoveVertex(2)),
+            Err(TwoPTwoPGraphError::VertexHasEdges)
+        );
+        assert_eq!(
+            update(&mut payload, TwoPTwoPGraphOp::RemoveEdge(2, 1)),
+            Err(TwoPTwoPGraphError::EdgeNotFound)
+        );
+    }
+
+    #[test]
+    fn test_concurrent_remove_vertex_and_add_edge() {
+        let mut replica1 = TwoPTwoPGraph::new();
+        let setup = [TwoPTwoPGraphOp::AddVertex(1), TwoPTwoPGraphOp::AddVertex(2)];
+        setup.iter().for_each(|op| replica1.downstream(op));
+        let mut replica2 = replica1.clone();
+
+        let remove = TwoPTwoPGraphOp::RemoveVertex(2);
+        let add_edge = TwoPTwoPGraphOp::AddEdge(1, 2);
+        replica1.downstream(&remove);
+        replica2.downstream(&add_edge);
+        replica1.downstream(&add_edge);
+        replica2.downstream(&remove);
+
+        // The dangling edge is hidden by the removed vertex.
+        assert_eq!(replica1, replica2);
+        assert!(!replica1.lookup_edge(&1, &2));
+        assert_eq!(replica1.edges().count(), 0);
+    }
+}
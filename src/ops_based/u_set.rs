@@ -0,0 +1,190 @@
+//! Operation-based U-Set
+//!
+//! A 2P-Set specialised to elements that are unique, i.e. added at most once.
+//! Under causal delivery a remove always follows its add, so removed
+//! elements can be dropped instead of kept as tombstones.
+//!
+//! ```txt
+//! payload set S
+//!   initial ∅
+//! query lookup (element e) : boolean b
+//!   let b = (e ∈ S)
+//! update add (element e)
+//!   atSource (e)
+//!     pre e is unique
+//!   downstream (e)
+//!     S := S ∪ {e}
+//! update remove (element e)
+//!   atSource (e)
+//!     pre lookup(e) // 2P-Set precondition
+//!   downstream (e)
+//!     pre add(e) has been delivered // Causal order suffices
+//!     S := S \ {e}
+//! ```
+
+use std::{collections::BTreeSet, error, fmt};
+
+use super::OpsBased;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum USetError {
+    /// Uniqueness can only be checked against the elements currently present.
+    AlreadyPresent,
+    NotFound,
+}
+
+impl fmt::Display for USetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyPresent => write!(f, "element is already present"),
+            Self::NotFound => write!(f, "element is not present"),
+        }
+    }
+}
+
+impl error::Error for USetError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum USetOp<T> {
+    Add(T),
+    Remove(T),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct USet<T> {
+    s: BTreeSet<T>,
+}
+
+impl<T> Default for USet<T> {
+    fn default() -> Self {
+        Self { s: BTreeSet::new() }
+    }
+}
+
+impl<T> USet<T>
+where
+    T: Ord + Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lookup(&self, element: &T) -> bool {
+        self.s.contains(element)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.s.iter()
+    }
+
+    /// Neither update returns anything at the source.
+    pub fn at_source(&mut self, _op: &USetOp<T>) -> Option<USet<T>> {
+        None
+    }
+
+    /// Removing an absent element is a no-op. Concurrent removes of the same
+    /// element make this unavoidable, since once removed the element leaves no
+    /// trace; for the same reason a remove delivered before its add cannot be
+    /// detected and is lost, so ops must be delivered in causal order.
+    pub fn downstream(&mut self, op: &USetOp<T>) {
+        match op {
+            USetOp::Add(element) => self.s.insert(element.clone()),
+            USetOp::Remove(element) => self.s.remove(element),
+        };
+    }
+}
+
+impl<T> OpsBased<USet<T>> for USet<T>
+where
+    T: Ord + Clone,
+{
+    type Query = fn(&USet<T>) -> Option<USet<T>>;
+    type Args = USetOp<T>;
+    type AtSource = fn(&mut USet<T>, &Self::Args) -> Option<USet<T>>;
+    type Downstream = fn(&mut USet<T>, &Self::Args);
+    type Error = USetError;
+
+    fn query(&self, query: Self::Query) -> Result<Option<USet<T>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(
+        &mut self,
+        args: &Self::Args,
+        at_source: Self::AtSource,
+        downstream: Self::Downstream,
+    ) -> Result<Option<USet<T>>, Self::Error> {
+        match args {
+            USetOp::Add(element) if self.lookup(element) => {
+                return Err(USetError::AlreadyPresent);
+            }
+            USetOp::Remove(element) if !self.lookup(element) => {
+                return Err(USetError::NotFound);
+            }
+            _ => {}
+        }
+        let res = at_source(self, args);
+        downstream(self, args);
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops_based::Payload;
+
+    fn update(payload: &mut Payload<USet<u32>>, op: USetOp<u32>) -> Result<(), USetError> {
+        payload
+            .update(&op, USet::at_source, USet::downstream)
+            .map(|_| ())
+    }
+
+    #[test]
+    fn test_update() {
+        let mut payload = Payload::new(USet::new());
+        update(&mut payload, USetOp::Add(1)).unwrap();
+        update(&mut payload, USetOp::Add(2)).unwrap();
+        update(&mut payload, USetOp::Remove(1)).unwrap();
+
+        let set = payload.query(|set| Some(set.clone())).unwrap().unwrap();
+        assert!(!set.lookup(&1));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![&2]);
+    }
+
+    #[test]
+    fn test_update_preconditions() {
+        let mut payload = Payload::new(USet::new());
+        assert_eq!(
+            update(&mut payload, USetOp::Remove(1)),
+            Err(USetError::NotFound)
+        );
+        update(&mut payload, USetOp::Add(1)).unwrap();
+        assert_eq!(
+            update(&mut payload, USetOp::Add(1)),
+            Err(USetError::AlreadyPresent)
+        );
+    }
+
+    #[test]
+    fn test_downstream_leaves_no_tombstones() {
+        let mut replica1 = USet::new();
+        let mut replica2 = USet::new();
+        [USetOp::Add(1), USetOp::Add(2), USetOp::Remove(1)]
+            .iter()
+            .for_each(|op| replica1.downstream(op));
+        [USetOp::Add(2)]
+            .iter()
+            .for_each(|op| replica2.downstream(op));
+        assert_eq!(replica1, replica2);
+    }
+
+    #[test]
+    fn test_downstream_concurrent_removes() {
+        let mut replica = USet::new();
+        replica.downstream(&USetOp::Add(1));
+        replica.downstream(&USetOp::Remove(1));
+        replica.downstream(&USetOp::Remove(1));
+        assert!(!replica.lookup(&1));
+    }
+}
@@ -16,15 +16,75 @@
 //!   LUB merge of value1 and value2, at any replica
 //! ```
 
+pub mod bounded_counter;
+pub mod cl_set;
+pub mod g_counter;
+pub mod g_set;
+pub mod lww_element_set;
+pub mod lww_map;
+pub mod lww_register;
+pub mod mv_register;
+pub mod or_map;
+pub mod orswot;
+pub mod pn_counter;
+pub mod pn_set;
+pub mod remove_wins_set;
+pub mod shopping_cart;
+pub mod two_p_set;
+pub mod version_vector;
+
+pub use bounded_counter::{BoundedCounter, BoundedCounterError};
+pub use cl_set::CLSet;
+pub use g_counter::GCounter;
+pub use g_set::GSet;
+pub use lww_element_set::{AddBias, Bias, LwwElementSet, RemoveBias};
+pub use lww_map::LwwMap;
+pub use lww_register::LwwRegister;
+pub use mv_register::MVRegister;
+pub use or_map::ORMap;
+pub use orswot::Orswot;
+pub use pn_counter::PNCounter;
+pub use pn_set::PNSet;
+pub use remove_wins_set::RemoveWinsSet;
+pub use shopping_cart::ShoppingCart;
+pub use two_p_set::{TwoPSet, TwoPSetError};
+pub use version_vector::{Dot, VersionVector};
+
+use std::cmp::Ordering;
+
+use crate::ReplicaId;
+
 pub trait Semilattice {
     fn compare(&self, other: &Self) -> bool;
 
     fn merge(&self, other: &Self) -> Self;
+
+    /// Position of `self` relative to `other` in the semilattice, `None` when
+    /// they are concurrent. Derived from `compare` in both directions unless
+    /// the payload can tell in a single pass.
+    fn partial_compare(&self, other: &Self) -> Option<Ordering> {
+        match (self.compare(other), other.compare(self)) {
+            (true, true) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (false, false) => None,
+        }
+    }
+}
+
+/// Set supporting add, remove and re-add, whose implementors differ in how a
+/// remove concurrent with an add of the same element is resolved.
+pub trait ObservedRemoveSet<T>: Semilattice {
+    fn lookup(&self, element: &T) -> bool;
+
+    fn add(&mut self, replica: ReplicaId, element: T);
+
+    fn remove(&mut self, replica: ReplicaId, element: &T);
 }
 
 pub trait StateBased<T> {
     type Query: FnOnce(&T) -> Option<T>;
-    type Update: FnOnce(&mut T) -> Option<T>;
+    type Update: FnOnce(&mut T) -> Result<Option<T>, Self::Error>;
     type Error;
 
     fn query(&self, query: Self::Query) -> Result<Option<T>, Self::Error>;
@@ -37,16 +97,26 @@ pub struct Payload<T> {
     initial: T,
 }
 
+impl<T> Payload<T> {
+    pub fn new(initial: T) -> Self {
+        Self { initial }
+    }
+}
+
 impl<T> Payload<T>
 where
     T: Semilattice + StateBased<T>,
 {
     pub fn query(&self, query: T::Query) -> Result<Option<T>, T::Error> {
-        Ok(query(&self.initial))
+        self.initial.query(query)
     }
 
     pub fn update(&mut self, update: T::Update) -> Result<Option<T>, T::Error> {
-        Ok(update(&mut self.initial))
+        self.initial.update(update)
+    }
+
+    pub fn merge(&mut self, other: &Payload<T>) {
+        self.initial = self.initial.merge(&other.initial);
     }
 }
 
@@ -68,7 +138,7 @@ mod tests {
 
     impl StateBased<i32> for i32 {
         type Query = fn(&i32) -> Option<i32>;
-        type Update = fn(&mut i32) -> Option<i32>;
+        type Update = fn(&mut i32) -> Result<Option<i32>, Infallible>;
         type Error = Infallible;
 
         fn query(&self, query: Self::Query) -> Result<Option<i32>, Self::Error> {
@@ -76,7 +146,7 @@ mod tests {
         }
 
         fn update(&mut self, update: Self::Update) -> Result<Option<i32>, Self::Error> {
-            Ok(update(self))
+            update(self)
         }
     }
 
@@ -88,6 +158,21 @@ mod tests {
         assert!(!value2.compare(&value1));
     }
 
+    #[test]
+    fn test_partial_compare() {
+        let value1: i32 = 1;
+        let value2: i32 = 2;
+        assert_eq!(value1.partial_compare(&value2), Some(Ordering::Less));
+        assert_eq!(value2.partial_compare(&value1), Some(Ordering::Greater));
+        assert_eq!(value1.partial_compare(&value1), Some(Ordering::Equal));
+
+        let mut counter1 = GCounter::new();
+        let mut counter2 = GCounter::new();
+        counter1.increment(0);
+        counter2.increment(1);
+        assert_eq!(counter1.partial_compare(&counter2), None);
+    }
+
     #[test]
     fn test_merge() {
         let value1: i32 = 2;
@@ -108,7 +193,7 @@ mod tests {
         let mut payload = Payload { initial: 1 };
         let update = |value: &mut i32| {
             *value += 1;
-            Some(*value)
+            Ok(Some(*value))
         };
         assert_eq!(payload.update(update).unwrap().unwrap(), 2);
         assert_eq!(payload.initial, 2);
@@ -0,0 +1,253 @@
+//! State-based bounded counter
+//!
+//! A PN-Counter whose value never drops below zero. Every increment grants
+//! rights to the replica issuing it, a replica may only decrement by the
+//! rights it currently holds, and rights can be transferred to other
+//! replicas that run out of them.
+//!
+//! ```txt
+//! payload integer[n][n] R, integer[n] U
+//!   initial [[0, ..., 0], ...], [0, ..., 0]
+//! query value () : integer v
+//!   let v = Σi R[i][i] - Σi U[i]
+//! query localRights (i) : integer r
+//!   let r = R[i][i] + Σj≠i R[j][i] - Σj≠i R[i][j] - U[i]
+//! update increment (integer n)
+//!   let g = myID()
+//!   R[g][g] := R[g][g] + n
+//! update decrement (integer n)
+//!   pre localRights(myID()) ≥ n
+//!   let g = myID()
+//!   U[g] := U[g] + n
+//! update transfer (integer n, replica j)
+//!   pre localRights(myID()) ≥ n
+//!   let g = myID()
+//!   R[g][j] := R[g][j] + n
+//! compare (X, Y) : boolean b
+//!   let b = (∀i, j : X.R[i][j] ≤ Y.R[i][j] ∧ ∀i : X.U[i] ≤ Y.U[i])
+//! merge (X, Y) : payload Z
+//!   let ∀i, j : Z.R[i][j] = max(X.R[i][j], Y.R[i][j])
+//!   let ∀i : Z.U[i] = max(X.U[i], Y.U[i])
+//! ```
+
+use std::{collections::BTreeMap, error, fmt};
+
+use super::{Semilattice, StateBased};
+use crate::ReplicaId;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoundedCounterError {
+    InsufficientRights {
+        replica: ReplicaId,
+        requested: u64,
+        available: u64,
+    },
+}
+
+impl fmt::Display for BoundedCounterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InsufficientRights {
+                replica,
+                requested,
+                available,
+            } => write!(
+                f,
+                "replica {replica} requested {requested} rights but only holds {available}"
+            ),
+        }
+    }
+}
+
+impl error::Error for BoundedCounterError {}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BoundedCounter {
+    rights: BTreeMap<(ReplicaId, ReplicaId), u64>,
+    used: BTreeMap<ReplicaId, u64>,
+}
+
+impl BoundedCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn value(&self) -> u64 {
+        let granted: u64 = self
+            .rights
+            .iter()
+            .filter(|((from, to), _)| from == to)
+            .map(|(_, n)| n)
+            .sum();
+        granted - self.used.values().sum::<u64>()
+    }
+
+    pub fn local_rights(&self, replica: ReplicaId) -> u64 {
+        let (mut gained, mut spent) = (0, self.used.get(&replica).copied().unwrap_or(0));
+        for ((from, to), n) in &self.rights {
+            if *to == replica {
+                gained += n;
+            } else if *from == replica {
+                spent += n;
+            }
+        }
+        gained - spent
+    }
+
+    pub fn increment(&mut self, replica: ReplicaId, amount: u64) {
+        *self.rights.entry((replica, replica)).or_insert(0) += amount;
+    }
+
+    pub fn decrement(
+        &mut self,
+        replica: ReplicaId,
+        amount: u64,
+    ) -> Result<(), BoundedCounterError> {
+        self.check_rights(replica, amount)?;
+        *self.used.entry(replica).or_insert(0) += amount;
+        Ok(())
+    }
+
+    pub fn transfer(
+        &mut self,
+        from: ReplicaId,
+        to: ReplicaId,
+        amount: u64,
+    ) -> Result<(), BoundedCounterError> {
+        if from == to {
+            return Ok(());
+        }
+        self.check_rights(from, amount)?;
+        *self.rights.entry((from, to)).or_insert(0) += amount;
+        Ok(())
+    }
+
+    fn check_rights(&self, replica: ReplicaId, requested: u64) -> Result<(), BoundedCounterError> {
+        let available = self.local_rights(replica);
+        if requested > available {
+            return Err(BoundedCounterError::InsufficientRights {
+                replica,
+                requested,
+                available,
+            });
+        }
+        Ok(())
+    }
+}
+
+fn le<K: Ord>(x: &BTreeMap<K, u64>, y: &BTreeMap<K, u64>) -> bool {
+    x.iter().all(|(k, n)| *n <= y.get(k).copied().unwrap_or(0))
+}
+
+fn max<K: Ord + Clone>(x: &BTreeMap<K, u64>, y: &BTreeMap<K, u64>) -> BTreeMap<K, u64> {
+    let mut z = x.clone();
+    for (k, n) in y {
+        let entry = z.entry(k.clone()).or_insert(0);
+        *entry = (*entry).max(*n);
+    }
+    z
+}
+
+impl Semilattice for BoundedCounter {
+    fn compare(&self, other: &Self) -> bool {
+        le(&self.rights, &other.rights) && le(&self.used, &other.used)
+    }
+
+    fn merge(&self, other: &Self) -> Self {
+        Self {
+            rights: max(&self.rights, &other.rights),
+            used: max(&self.used, &other.used),
+        }
+    }
+}
+
+impl StateBased<BoundedCounter> for BoundedCounter {
+    type Query = fn(&BoundedCounter) -> Option<BoundedCounter>;
+    type Update = fn(&mut BoundedCounter) -> Result<Option<BoundedCounter>, BoundedCounterError>;
+    type Error = BoundedCounterError;
+
+    fn query(&self, query: Self::Query) -> Result<Option<BoundedCounter>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(&mut self, update: Self::Update) -> Result<Option<BoundedCounter>, Self::Error> {
+        update(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_based::Payload;
+
+    #[test]
+    fn test_decrement() {
+        let mut counter = BoundedCounter::new();
+        counter.increment(0, 3);
+        counter.decrement(0, 2).unwrap();
+        assert_eq!(counter.value(), 1);
+        assert_eq!(
+            counter.decrement(0, 2),
+            Err(BoundedCounterError::InsufficientRights {
+                replica: 0,
+                requested: 2,
+                available: 1,
+            })
+        );
+        assert_eq!(counter.value(), 1);
+    }
+
+    #[test]
+    fn test_transfer() {
+        let mut counter = BoundedCounter::new();
+        counter.increment(0, 5);
+        assert!(counter.decrement(1, 1).is_err());
+
+        counter.transfer(0, 1, 2).unwrap();
+        assert_eq!(counter.local_rights(0), 3);
+        assert_eq!(counter.local_rights(1), 2);
+        counter.decrement(1, 2).unwrap();
+        assert_eq!(counter.value(), 3);
+        assert!(counter.transfer(1, 0, 1).is_err());
+    }
+
+    #[test]
+    fn test_merge_never_below_zero() {
+        let mut replica0 = BoundedCounter::new();
+        replica0.increment(0, 2);
+        replica0.transfer(0, 1, 1).unwrap();
+        let mut replica1 = replica0.clone();
+
+        // Both replicas drain what they hold concurrently.
+        replica0.decrement(0, 1).unwrap();
+        replica1.decrement(1, 1).unwrap();
+        assert!(replica0.decrement(0, 1).is_err());
+        assert!(replica1.decrement(1, 1).is_err());
+
+        let merged = replica0.merge(&replica1);
+        assert_eq!(merged, replica1.merge(&replica0));
+        assert_eq!(merged.value(), 0);
+        assert!(replica0.compare(&merged));
+        assert!(replica1.compare(&merged));
+    }
+
+    #[test]
+    fn test_payload_update_error() {
+        let mut counter = BoundedCounter::new();
+        counter.increment(0, 1);
+        let mut payload = Payload::new(counter);
+
+        let result = payload.update(|counter| {
+            counter.decrement(0, 2)?;
+            Ok(None)
+        });
+        assert_eq!(
+            result,
+            Err(BoundedCounterError::InsufficientRights {
+                replica: 0,
+                requested: 2,
+                available: 1,
+            })
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! State-based causal length set (CLSet)
+//!
+//! Every element tracks its causal length, the number of times it was added
+//! or removed along the longest causal history. Odd lengths mean present,
+//! even lengths absent, and merge takes the maximum length.
+//!
+//! ```txt
+//! payload set S // set of (element, length) pairs
+//!   initial ∅ // a missing element has length 0
+//! query lookup (element e) : boolean b
+//!   let b = (S[e] is odd)
+//! update add (element e)
+//!   if S[e] is even then S[e] := S[e] + 1
+//! update remove (element e)
+//!   if S[e] is odd then S[e] := S[e] + 1
+//! compare (X, Y) : boolean b
+//!   let b = (∀e : X.S[e] ≤ Y.S[e])
+//! merge (X, Y) : payload Z
+//!   let ∀e : Z.S[e] = max(X.S[e], Y.S[e])
+//! ```
+
+use std::{collections::BTreeMap, convert::Infallible};
+
+use super::{Semilattice, StateBased};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CLSet<T> {
+    s: BTreeMap<T, u64>,
+}
+
+impl<T> Default for CLSet<T> {
+    fn default() -> Self {
+        Self { s: BTreeMap::new() }
+    }
+}
+
+/// An element is in the set iff its causal length is odd.
+fn is_present(length: u64) -> bool {
+    length % 2 == 1
+}
+
+impl<T> CLSet<T>
+where
+    T: Ord,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lookup(&self, element: &T) -> bool {
+        is_present(self.length(element))
+    }
+
+    pub fn length(&self, element: &T) -> u64 {
+        self.s.get(element).copied().unwrap_or(0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.s
+            .iter()
+            .filter(|(_, length)| is_present(**length))
+            .map(|(element, _)| element)
+    }
+
+    pub fn add(&mut self, element: T) {
+        let length = self.s.entry(element).or_insert(0);
+        if !is_present(*length) {
+            *length += 1;
+        }
+    }
+
+    pub fn remove(&mut self, element: T) {
+        if let Some(length) = self.s.get_mut(&element) {
+            if is_present(*length) {
+                *length += 1;
+            }
+        }
+    }
+}
+
+impl<T> Semilattice for CLSet<T>
+where
+    T: Ord + Clone,
+{
+    fn compare(&self, other: &Self) -> bool {
+        self.s
+            .iter()
+            .all(|(element, length)| *length <= other.length(element))
+    }
+
+    fn merge(&self, other: &Self) -> Self {
+        let mut s = self.s.clone();
+        for (element, length) in &other.s {
+            let entry = s.entry(element.clone()).or_insert(0);
+            *entry = (*entry).max(*length);
+        }
+        Self { s }
+    }
+}
+
+impl<T> StateBased<CLSet<T>> for CLSet<T> {
+    type Query = fn(&CLSet<T>) -> Option<CLSet<T>>;
+    type Update = fn(&mut CLSet<T>) -> Result<Option<CLSet<T>>, Infallible>;
+    type Error = Infallible;
+
+    fn query(&self, query: Self::Query) -> Result<Option<CLSet<T>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(&mut self, update: Self::Update) -> Result<Option<CLSet<T>>, Self::Error> {
+        update(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_remove() {
+        let mut set = CLSet::new();
+        set.remove("a");
+        assert_eq!(set.length(&"a"), 0);
+        set.add("a");
+        set.add("a");
+        assert_eq!(set.length(&"a"), 1);
+        set.remove("a");
+        assert!(!set.lookup(&"a"));
+        set.add("a");
+        assert!(set.lookup(&"a"));
+        assert_eq!(set.length(&"a"), 3);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut set1 = CLSet::new();
+        set1.add("a");
+        let mut set2 = set1.clone();
+        set2.remove("a");
+        set1.add("b");
+        assert!(!set1.compare(&set2));
+
+        let merged = set1.merge(&set2);
+        assert_eq!(merged, set2.merge(&set1));
+        assert!(set1.compare(&merged) && set2.compare(&merged));
+        assert_eq!(merged.iter().collect::<Vec<_>>(), vec![&"b"]);
+    }
+
+    #[test]
+    fn test_merge_concurrent_re_adds() {
+        let mut set1 = CLSet::new();
+        set1.add("a");
+        set1.remove("a");
+        let mut set2 = set1.clone();
+        set1.add("a");
+        set2.add("a");
+
+        let merged = set1.merge(&set2);
+        assert!(merged.lookup(&"a"));
+        assert_eq!(merged.length(&"a"), 3);
+    }
+}
@@ -0,0 +1,142 @@
+//! State-based increment-only counter (G-Counter)
+//!
+//! ```txt
+//! payload integer[n] P
+//!   initial [0, 0, ..., 0]
+//! update increment ()
+//!   let g = myID()
+//!   P[g] := P[g] + 1
+//! query value () : integer v
+//!   let v = Σi P[i]
+//! compare (X, Y) : boolean b
+//!   let b = (∀i ∈ [0, n - 1] : X.P[i] ≤ Y.P[i])
+//! merge (X, Y) : payload Z
+//!   let ∀i ∈ [0, n - 1] : Z.P[i] = max(X.P[i], Y.P[i])
+//! ```
+
+use std::{collections::BTreeMap, convert::Infallible};
+
+use super::{Semilattice, StateBased};
+use crate::ReplicaId;
+
+/// Replicas are not fixed up front, so `P` is kept as a map where a missing
+/// entry stands for `0`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GCounter {
+    counts: BTreeMap<ReplicaId, u64>,
+}
+
+impl GCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn increment(&mut self, replica: ReplicaId) {
+        *self.counts.entry(replica).or_insert(0) += 1;
+    }
+
+    /// `P[replica]`, the number of increments originating at `replica`.
+    pub fn get(&self, replica: ReplicaId) -> u64 {
+        self.counts.get(&replica).copied().unwrap_or(0)
+    }
+
+    pub fn value(&self) -> u64 {
+        self.counts.values().sum()
+    }
+}
+
+impl Semilattice for GCounter {
+    fn compare(&self, other: &Self) -> bool {
+        self.counts
+            .iter()
+            .all(|(replica, count)| *count <= other.get(*replica))
+    }
+
+    fn merge(&self, other: &Self) -> Self {
+        let mut counts = self.counts.clone();
+        for (replica, count) in &other.counts {
+            let entry = counts.entry(*replica).or_insert(0);
+            *entry = (*entry).max(*count);
+        }
+        Self { counts }
+    }
+}
+
+impl StateBased<GCounter> for GCounter {
+    type Query = fn(&GCounter) -> Option<GCounter>;
+    type Update = fn(&mut GCounter) -> Result<Option<GCounter>, Infallible>;
+    type Error = Infallible;
+
+    fn query(&self, query: Self::Query) -> Result<Option<GCounter>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(&mut self, update: Self::Update) -> Result<Option<GCounter>, Self::Error> {
+        update(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_based::Payload;
+
+    #[test]
+    fn test_value() {
+        let mut counter = GCounter::new();
+        counter.increment(0);
+        counter.increment(0);
+        counter.increment(1);
+        assert_eq!(counter.value(), 3);
+        assert_eq!(counter.get(0), 2);
+        assert_eq!(counter.get(2), 0);
+    }
+
+    #[test]
+    fn test_compare() {
+        let mut counter1 = GCounter::new();
+        counter1.increment(0);
+        let mut counter2 = counter1.clone();
+        counter2.increment(1);
+        assert!(counter1.compare(&counter2));
+        assert!(!counter2.compare(&counter1));
+
+        counter1.increment(0);
+        assert!(!counter1.compare(&counter2));
+        assert!(!counter2.compare(&counter1));
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut counter1 = GCounter::new();
+        let mut counter2 = GCounter::new();
+        counter1.increment(0);
+        counter1.increment(0);
+        counter2.increment(0);
+        counter2.increment(1);
+
+        let merged = counter1.merge(&counter2);
+        assert_eq!(merged, counter2.merge(&counter1));
+        assert_eq!(merged.value(), 3);
+        assert!(counter1.compare(&merged));
+        assert!(counter2.compare(&merged));
+    }
+
+    #[test]
+    fn test_payload() {
+        let mut payload = Payload::new(GCounter::new());
+        payload
+            .update(|counter| {
+                counter.increment(0);
+                Ok(None)
+            })
+            .unwrap();
+
+        let mut other = GCounter::new();
+        other.increment(1);
+        payload.merge(&Payload::new(other));
+
+        let value = payload.query(|counter| Some(counter.clone())).unwrap();
+        assert_eq!(value.unwrap().value(), 2);
+    }
+}
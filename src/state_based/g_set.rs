@@ -0,0 +1,108 @@
+//! State-based grow-only set (G-Set)
+//!
+//! ```txt
+//! payload set A
+//!   initial ∅
+//! update add (element e)
+//!   A := A ∪ {e}
+//! query lookup (element e) : boolean b
+//!   let b = (e ∈ A)
+//! compare (S, T) : boolean b
+//!   let b = (S.A ⊆ T.A)
+//! merge (S, T) : payload U
+//!   let U.A = S.A ∪ T.A
+//! ```
+
+use std::{collections::BTreeSet, convert::Infallible};
+
+use super::{Semilattice, StateBased};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GSet<T> {
+    a: BTreeSet<T>,
+}
+
+impl<T> Default for GSet<T> {
+    fn default() -> Self {
+        Self { a: BTreeSet::new() }
+    }
+}
+
+impl<T> GSet<T>
+where
+    T: Ord,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, element: T) {
+        self.a.insert(element);
+    }
+
+    pub fn lookup(&self, element: &T) -> bool {
+        self.a.contains(element)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.a.iter()
+    }
+}
+
+impl<T> Semilattice for GSet<T>
+where
+    T: Ord + Clone,
+{
+    fn compare(&self, other: &Self) -> bool {
+        self.a.is_subset(&other.a)
+    }
+
+    fn merge(&self, other: &Self) -> Self {
+        Self {
+            a: self.a.union(&other.a).cloned().collect(),
+        }
+    }
+}
+
+impl<T> StateBased<GSet<T>> for GSet<T> {
+    type Query = fn(&GSet<T>) -> Option<GSet<T>>;
+    type Update = fn(&mut GSet<T>) -> Result<Option<GSet<T>>, Infallible>;
+    type Error = Infallible;
+
+    fn query(&self, query: Self::Query) -> Result<Option<GSet<T>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(&mut self, update: Self::Update) -> Result<Option<GSet<T>>, Self::Error> {
+        update(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        let mut set = GSet::new();
+        set.add(1);
+        set.add(1);
+        assert!(set.lookup(&1));
+        assert!(!set.lookup(&2));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![&1]);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut set1 = GSet::new();
+        let mut set2 = GSet::new();
+        set1.add(1);
+        set2.add(2);
+        assert!(!set1.compare(&set2));
+
+        let merged = set1.merge(&set2);
+        assert_eq!(merged, set2.merge(&set1));
+        assert!(set1.compare(&merged) && set2.compare(&merged));
+        assert_eq!(merged.iter().collect::<Vec<_>>(), vec![&1, &2]);
+    }
+}
@@ -0,0 +1,199 @@
+//! State-based last-writer-wins element set (LWW-element-Set)
+//!
+//! Every element keeps the timestamp of its latest add and latest remove, so
+//! an element can be re-added after removal. Whether an add and a remove
+//! stamped with the same timestamp leave the element present is decided by
+//! the [`Bias`] of the set.
+//!
+//! ```txt
+//! payload set A, set R // A: added, R: removed; sets of (element, timestamp)
+//!   initial ∅, ∅
+//! query lookup (element e) : boolean b
+//!   let b = (∃(e, t) ∈ A : ∀(e, t') ∈ R : t > t') // t ≥ t' when biased towards add
+//! update add (element e)
+//!   A := A ∪ {(e, now())}
+//! update remove (element e)
+//!   R := R ∪ {(e, now())}
+//! compare (S, T) : boolean b
+//!   let b = (S.A ⊆ T.A ∧ S.R ⊆ T.R)
+//! merge (S, T) : payload U
+//!   let U.A = S.A ∪ T.A
+//!   let U.R = S.R ∪ T.R
+//! ```
+
+use std::{collections::BTreeMap, convert::Infallible, marker::PhantomData};
+
+use super::{Semilattice, StateBased};
+use crate::clock::{Clock, Timestamp};
+
+/// Resolves an add and a remove of the same element.
+pub trait Bias {
+    fn lookup(added: Timestamp, removed: Timestamp) -> bool;
+}
+
+/// Ties favor add.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AddBias;
+
+impl Bias for AddBias {
+    fn lookup(added: Timestamp, removed: Timestamp) -> bool {
+        added >= removed
+    }
+}
+
+/// Ties favor remove.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RemoveBias;
+
+impl Bias for RemoveBias {
+    fn lookup(added: Timestamp, removed: Timestamp) -> bool {
+        added > removed
+    }
+}
+
+/// Only the greatest timestamp of each element is kept in `A` and `R`, which
+/// is all `lookup` ever looks at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LwwElementSet<T, B = AddBias> {
+    a: BTreeMap<T, Timestamp>,
+    r: BTreeMap<T, Timestamp>,
+    bias: PhantomData<B>,
+}
+
+impl<T, B> Default for LwwElementSet<T, B> {
+    fn default() -> Self {
+        Self {
+            a: BTreeMap::new(),
+            r: BTreeMap::new(),
+            bias: PhantomData,
+        }
+    }
+}
+
+fn stamp<T: Ord>(set: &mut BTreeMap<T, Timestamp>, element: T, timestamp: Timestamp) {
+    let entry = set.entry(element).or_insert(timestamp);
+    *entry = (*entry).max(timestamp);
+}
+
+fn le<T: Ord>(x: &BTreeMap<T, Timestamp>, y: &BTreeMap<T, Timestamp>) -> bool {
+    x.iter()
+        .all(|(element, t)| y.get(element).is_some_and(|other| t <= other))
+}
+
+fn union<T: Ord + Clone>(
+    x: &BTreeMap<T, Timestamp>,
+    y: &BTreeMap<T, Timestamp>,
+) -> BTreeMap<T, Timestamp> {
+    let mut z = x.clone();
+    for (element, t) in y {
+        stamp(&mut z, element.clone(), *t);
+    }
+    z
+}
+
+impl<T, B> LwwElementSet<T, B>
+where
+    T: Ord,
+    B: Bias,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lookup(&self, element: &T) -> bool {
+        match (self.a.get(element), self.r.get(element)) {
+            (Some(added), Some(removed)) => B::lookup(*added, *removed),
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.a.keys().filter(|element| self.lookup(element))
+    }
+
+    pub fn add<C: Clock>(&mut self, element: T, clock: &mut C) {
+        stamp(&mut self.a, element, clock.now());
+    }
+
+    pub fn remove<C: Clock>(&mut self, element: T, clock: &mut C) {
+        stamp(&mut self.r, element, clock.now());
+    }
+}
+
+impl<T, B> Semilattice for LwwElementSet<T, B>
+where
+    T: Ord + Clone,
+{
+    fn compare(&self, other: &Self) -> bool {
+        le(&self.a, &other.a) && le(&self.r, &other.r)
+    }
+
+    fn merge(&self, other: &Self) -> Self {
+        Self {
+            a: union(&self.a, &other.a),
+            r: union(&self.r, &other.r),
+            bias: PhantomData,
+        }
+    }
+}
+
+impl<T, B> StateBased<LwwElementSet<T, B>> for LwwElementSet<T, B> {
+    type Query = fn(&LwwElementSet<T, B>) -> Option<LwwElementSet<T, B>>;
+    type Update = fn(&mut LwwElementSet<T, B>) -> Result<Option<LwwElementSet<T, B>>, Infallible>;
+    type Error = Infallible;
+
+    fn query(&self, query: Self::Query) -> Result<Option<LwwElementSet<T, B>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(&mut self, update: Self::Update) -> Result<Option<LwwElementSet<T, B>>, Self::Error> {
+        update(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::LogicalClock;
+
+    #[test]
+    fn test_re_add() {
+        let mut clock = LogicalClock::default();
+        let mut set: LwwElementSet<&str> = LwwElementSet::new();
+        set.add("rust", &mut clock);
+        set.remove("rust", &mut clock);
+        assert!(!set.lookup(&"rust"));
+        set.add("rust", &mut clock);
+        assert!(set.lookup(&"rust"));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![&"rust"]);
+    }
+
+    #[test]
+    fn test_bias() {
+        let mut add_biased: LwwElementSet<&str, AddBias> = LwwElementSet::new();
+        add_biased.add("rust", &mut LogicalClock::default());
+        add_biased.remove("rust", &mut LogicalClock::default());
+        assert!(add_biased.lookup(&"rust"));
+
+        let mut remove_biased: LwwElementSet<&str, RemoveBias> = LwwElementSet::new();
+        remove_biased.add("rust", &mut LogicalClock::default());
+        remove_biased.remove("rust", &mut LogicalClock::default());
+        assert!(!remove_biased.lookup(&"rust"));
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut set1: LwwElementSet<&str> = LwwElementSet::new();
+        set1.add("rust", &mut LogicalClock::new(0));
+        let mut set2 = set1.clone();
+        set2.remove("rust", &mut LogicalClock::new(1));
+        set1.add("crdt", &mut LogicalClock::new(1));
+        assert!(!set1.compare(&set2));
+
+        let merged = set1.merge(&set2);
+        assert_eq!(merged, set2.merge(&set1));
+        assert!(set1.compare(&merged) && set2.compare(&merged));
+        assert_eq!(merged.iter().collect::<Vec<_>>(), vec![&"crdt"]);
+    }
+}
@@ -0,0 +1,167 @@
+//! State-based last-writer-wins map (LWW-Map)
+//!
+//! Every key holds an [`LwwRegister`] of an optional value, and a remove
+//! assigns `None` to it. The register then acts as a timestamped tombstone, so
+//! a write and a remove of the same key are resolved like two writes.
+//!
+//! ```txt
+//! payload map M // M: key -> LWW-Register of X ∪ {⊥}
+//!   initial ∅
+//! query get (key k) : X v
+//!   pre M[k].value() ≠ ⊥
+//!   let v = M[k].value()
+//! update insert (key k, X v)
+//!   M[k].assign(v)
+//! update remove (key k)
+//!   M[k].assign(⊥)
+//! compare (A, B) : boolean b
+//!   let b = (∀k ∈ A.M : A.M[k].compare(B.M[k]))
+//! merge (A, B) : payload C
+//!   let ∀k : C.M[k] = A.M[k].merge(B.M[k])
+//! ```
+
+use std::{collections::BTreeMap, convert::Infallible};
+
+use super::{LwwRegister, Semilattice, StateBased};
+use crate::{clock::Clock, ReplicaId};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LwwMap<K, V> {
+    m: BTreeMap<K, LwwRegister<Option<V>>>,
+}
+
+impl<K, V> Default for LwwMap<K, V> {
+    fn default() -> Self {
+        Self { m: BTreeMap::new() }
+    }
+}
+
+impl<K, V> LwwMap<K, V>
+where
+    K: Ord,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.m
+            .get(key)
+            .and_then(|register| register.value().as_ref())
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.m
+            .iter()
+            .filter_map(|(key, register)| register.value().as_ref().map(|value| (key, value)))
+    }
+
+    pub fn insert<C: Clock>(&mut self, replica: ReplicaId, key: K, value: V, clock: &mut C) {
+        self.assign(replica, key, Some(value), clock);
+    }
+
+    pub fn remove<C: Clock>(&mut self, replica: ReplicaId, key: K, clock: &mut C) {
+        self.assign(replica, key, None, clock);
+    }
+
+    fn assign<C: Clock>(&mut self, replica: ReplicaId, key: K, value: Option<V>, clock: &mut C) {
+        self.m
+            .entry(key)
+            .or_insert_with(|| LwwRegister::new(None))
+            .assign(replica, value, clock);
+    }
+}
+
+impl<K, V> Semilattice for LwwMap<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    fn compare(&self, other: &Self) -> bool {
+        self.m.iter().all(|(key, register)| {
+            other
+                .m
+                .get(key)
+                .is_some_and(|other| register.compare(other))
+        })
+    }
+
+    fn merge(&self, other: &Self) -> Self {
+        let mut m = self.m.clone();
+        for (key, register) in &other.m {
+            let merged = match m.get(key) {
+                Some(own) => own.merge(register),
+                None => register.clone(),
+            };
+            m.insert(key.clone(), merged);
+        }
+        Self { m }
+    }
+}
+
+impl<K, V> StateBased<LwwMap<K, V>> for LwwMap<K, V> {
+    type Query = fn(&LwwMap<K, V>) -> Option<LwwMap<K, V>>;
+    type Update = fn(&mut LwwMap<K, V>) -> Result<Option<LwwMap<K, V>>, Infallible>;
+    type Error = Infallible;
+
+    fn query(&self, query: Self::Query) -> Result<Option<LwwMap<K, V>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(&mut self, update: Self::Update) -> Result<Option<LwwMap<K, V>>, Self::Error> {
+        update(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{clock::LogicalClock, state_based::Payload};
+
+    #[test]
+    fn test_insert_remove() {
+        let mut clock = LogicalClock::default();
+        let mut map = LwwMap::new();
+        map.insert(0, "a", 1, &mut clock);
+        map.insert(0, "b", 2, &mut clock);
+        map.insert(0, "a", 3, &mut clock);
+        map.remove(0, "b", &mut clock);
+        assert_eq!(map.get(&"a"), Some(&3));
+        assert!(!map.contains_key(&"b"));
+        assert_eq!(map.iter().collect::<Vec<_>>(), vec![(&"a", &3)]);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut map1 = LwwMap::new();
+        map1.insert(0, "a", 1, &mut LogicalClock::new(0));
+        let mut map2 = map1.clone();
+        map1.remove(0, "a", &mut LogicalClock::new(1));
+        map2.insert(1, "a", 2, &mut LogicalClock::new(2));
+        map2.insert(1, "b", 3, &mut LogicalClock::new(2));
+        assert!(map1.compare(&map2) && !map2.compare(&map1));
+
+        let merged = map1.merge(&map2);
+        assert_eq!(merged, map2.merge(&map1));
+        assert!(map1.compare(&merged) && map2.compare(&merged));
+        assert_eq!(merged.get(&"a"), Some(&2));
+        assert_eq!(merged.get(&"b"), Some(&3));
+    }
+
+    #[test]
+    fn test_merge_tombstone() {
+        let mut map1 = LwwMap::new();
+        map1.insert(0, "a", 1, &mut LogicalClock::new(0));
+        let map2 = map1.clone();
+        map1.remove(0, "a", &mut LogicalClock::new(1));
+
+        let mut payload = Payload::new(map1);
+        payload.merge(&Payload::new(map2));
+        let map = payload.query(|map| Some(map.clone())).unwrap().unwrap();
+        assert!(!map.contains_key(&"a"));
+    }
+}
@@ -0,0 +1,140 @@
+//! State-based last-writer-wins register (LWW-Register)
+//!
+//! Ties between equal timestamps are broken by the id of the writing replica.
+//!
+//! ```txt
+//! payload X x, timestamp t // X: some type
+//!   initial ⊥, 0
+//! update assign (X w)
+//!   x, t := w, now() // Timestamp, consistent with causality
+//! query value () : X w
+//!   let w = x
+//! compare (R, R') : boolean b
+//!   let b = (R.t ≤ R'.t)
+//! merge (R, R') : payload R''
+//!   if R.t ≤ R'.t then R''.x, R''.t = R'.x, R'.t
+//!   else R''.x, R''.t = R.x, R.t
+//! ```
+
+use std::convert::Infallible;
+
+use super::{Semilattice, StateBased};
+use crate::{
+    clock::{Clock, Timestamp},
+    ReplicaId,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LwwRegister<V> {
+    value: V,
+    timestamp: Timestamp,
+    replica: ReplicaId,
+}
+
+impl<V> LwwRegister<V> {
+    /// `initial` plays the role of `⊥`, stamped with timestamp `0`.
+    pub fn new(initial: V) -> Self {
+        Self {
+            value: initial,
+            timestamp: 0,
+            replica: 0,
+        }
+    }
+
+    /// Stamps the new value strictly after the current one, so a local
+    /// assign always wins even if the clock lags behind a merged timestamp.
+    pub fn assign<C: Clock>(&mut self, replica: ReplicaId, value: V, clock: &mut C) {
+        self.value = value;
+        self.timestamp = clock.now().max(self.timestamp + 1);
+        self.replica = replica;
+    }
+
+    pub fn value(&self) -> &V {
+        &self.value
+    }
+
+    pub fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+
+    fn stamp(&self) -> (Timestamp, ReplicaId) {
+        (self.timestamp, self.replica)
+    }
+}
+
+impl<V> Semilattice for LwwRegister<V>
+where
+    V: Clone,
+{
+    fn compare(&self, other: &Self) -> bool {
+        self.stamp() <= other.stamp()
+    }
+
+    fn merge(&self, other: &Self) -> Self {
+        if self.compare(other) {
+            other.clone()
+        } else {
+            self.clone()
+        }
+    }
+}
+
+impl<V> StateBased<LwwRegister<V>> for LwwRegister<V> {
+    type Query = fn(&LwwRegister<V>) -> Option<LwwRegister<V>>;
+    type Update = fn(&mut LwwRegister<V>) -> Result<Option<LwwRegister<V>>, Infallible>;
+    type Error = Infallible;
+
+    fn query(&self, query: Self::Query) -> Result<Option<LwwRegister<V>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(&mut self, update: Self::Update) -> Result<Option<LwwRegister<V>>, Self::Error> {
+        update(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::LogicalClock;
+
+    #[test]
+    fn test_assign() {
+        let mut clock = LogicalClock::default();
+        let mut register = LwwRegister::new("");
+        register.assign(0, "a", &mut clock);
+        register.assign(0, "b", &mut clock);
+        assert_eq!(*register.value(), "b");
+        assert_eq!(register.timestamp(), 2);
+    }
+
+    #[test]
+    fn test_assign_lagging_clock() {
+        let mut register = LwwRegister::new("");
+        register.assign(0, "a", &mut LogicalClock::new(5));
+        register.assign(0, "b", &mut LogicalClock::default());
+        assert_eq!(*register.value(), "b");
+        assert_eq!(register.timestamp(), 7);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut register1 = LwwRegister::new("");
+        let mut register2 = LwwRegister::new("");
+        register1.assign(0, "a", &mut LogicalClock::new(1));
+        register2.assign(1, "b", &mut LogicalClock::new(0));
+        assert!(register2.compare(&register1));
+        assert_eq!(*register1.merge(&register2).value(), "a");
+        assert_eq!(*register2.merge(&register1).value(), "a");
+    }
+
+    #[test]
+    fn test_merge_tie() {
+        let mut register1 = LwwRegister::new("");
+        let mut register2 = LwwRegister::new("");
+        register1.assign(0, "a", &mut LogicalClock::default());
+        register2.assign(1, "b", &mut LogicalClock::default());
+        assert_eq!(*register1.merge(&register2).value(), "b");
+        assert_eq!(register1.merge(&register2), register2.merge(&register1));
+    }
+}
@@ -0,0 +1,177 @@
+//! State-based multi-value register (MV-Register)
+//!
+//! Concurrent assignments are all kept as siblings, each tagged with the
+//! version vector of its assignment. A later assignment overwrites every
+//! sibling it has observed.
+//!
+//! ```txt
+//! payload set S // set of (x, V) pairs; x ∈ X; V its version vector
+//!   initial {}
+//! query incVV () : integer[n] V'
+//!   let g = myID()
+//!   let 𝒱 = {V | ∃x : (x, V) ∈ S}
+//!   let V' = [max V∈𝒱 (V[j])]j≠g & [max V∈𝒱 (V[g]) + 1]g
+//! update assign (X x)
+//!   let V = incVV()
+//!   S := {(x, V)}
+//! query value () : set S'
+//!   let S' = {x | ∃V : (x, V) ∈ S}
+//! compare (A, B) : boolean b
+//!   let b = (∀(x, V) ∈ A, ∃(y, W) ∈ B : V ≤ W)
+//! merge (A, B) : payload C
+//!   let A' = {(x, V) ∈ A | ∀(y, W) ∈ B : V ‖ W ∨ V ≥ W}
+//!   let B' = {(y, W) ∈ B | ∀(x, V) ∈ A : W ‖ V ∨ W ≥ V}
+//!   let C = A' ∪ B'
+//! ```
+
+use std::convert::Infallible;
+
+use super::{Semilattice, StateBased, VersionVector};
+use crate::ReplicaId;
+
+#[derive(Debug, Clone)]
+pub struct MVRegister<V> {
+    entries: Vec<(V, VersionVector)>,
+}
+
+/// Siblings form a set, so equality ignores the order they were merged in.
+impl<V> PartialEq for MVRegister<V>
+where
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.entries.len() == other.entries.len()
+            && self
+                .entries
+                .iter()
+                .all(|entry| other.entries.contains(entry))
+    }
+}
+
+impl<V> Eq for MVRegister<V> where V: Eq {}
+
+impl<V> Default for MVRegister<V> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<V> MVRegister<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn assign(&mut self, replica: ReplicaId, value: V) {
+        let mut version = self
+            .entries
+            .iter()
+            .fold(VersionVector::new(), |acc, (_, version)| acc.merge(version));
+        version.increment(replica);
+        self.entries = vec![(value, version)];
+    }
+
+    /// The siblings written concurrently, empty until the first assignment.
+    pub fn values(&self) -> Vec<&V> {
+        self.entries.iter().map(|(value, _)| value).collect()
+    }
+}
+
+impl<V> Semilattice for MVRegister<V>
+where
+    V: Clone + PartialEq,
+{
+    fn compare(&self, other: &Self) -> bool {
+        self.entries
+            .iter()
+            .all(|(_, v)| other.entries.iter().any(|(_, w)| v <= w))
+    }
+
+    fn merge(&self, other: &Self) -> Self {
+        let mut entries: Vec<(V, VersionVector)> = self
+            .entries
+            .iter()
+            .filter(|(_, v)| !other.entries.iter().any(|(_, w)| v < w))
+            .cloned()
+            .collect();
+        for entry in &other.entries {
+            let (_, w) = entry;
+            if !self.entries.iter().any(|(_, v)| w < v) && !entries.contains(entry) {
+                entries.push(entry.clone());
+            }
+        }
+        Self { entries }
+    }
+}
+
+impl<V> StateBased<MVRegister<V>> for MVRegister<V> {
+    type Query = fn(&MVRegister<V>) -> Option<MVRegister<V>>;
+    type Update = fn(&mut MVRegister<V>) -> Result<Option<MVRegister<V>>, Infallible>;
+    type Error = Infallible;
+
+    fn query(&self, query: Self::Query) -> Result<Option<MVRegister<V>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(&mut self, update: Self::Update) -> Result<Option<MVRegister<V>>, Self::Error> {
+        update(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assign() {
+        let mut register = MVRegister::new();
+        assert!(register.values().is_empty());
+        register.assign(0, "a");
+        register.assign(0, "b");
+        assert_eq!(register.values(), vec![&"b"]);
+    }
+
+    #[test]
+    fn test_merge_concurrent() {
+        let mut register1 = MVRegister::new();
+        register1.assign(0, "a");
+        let mut register2 = register1.clone();
+        register1.assign(0, "b");
+        register2.assign(1, "c");
+        assert!(!register1.compare(&register2));
+        assert!(!register2.compare(&register1));
+
+        let merged = register1.merge(&register2);
+        assert_eq!(merged.values(), vec![&"b", &"c"]);
+        assert_eq!(merged, register2.merge(&register1));
+        assert!(register1.compare(&merged));
+        assert!(register2.compare(&merged));
+        assert_eq!(merged.merge(&merged), merged);
+    }
+
+    #[test]
+    fn test_merge_dominated() {
+        let mut register1 = MVRegister::new();
+        register1.assign(0, "a");
+        let mut register2 = register1.clone();
+        register2.assign(1, "b");
+        assert!(register1.compare(&register2));
+
+        assert_eq!(register1.merge(&register2).values(), vec![&"b"]);
+        assert_eq!(register2.merge(&register1).values(), vec![&"b"]);
+    }
+
+    #[test]
+    fn test_assign_after_merge() {
+        let mut register1 = MVRegister::new();
+        let mut register2 = MVRegister::new();
+        register1.assign(0, "a");
+        register2.assign(1, "b");
+        let mut merged = register1.merge(&register2);
+        merged.assign(1, "c");
+
+        assert_eq!(merged.merge(&register1).values(), vec![&"c"]);
+        assert_eq!(register2.merge(&merged).values(), vec![&"c"]);
+    }
+}
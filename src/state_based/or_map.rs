@@ -0,0 +1,245 @@
+//! State-based observed-remove map (OR-Map)
+//!
+//! Keys behave like the elements of an [`Orswot`](super::Orswot): every
+//! update of a key tags it with a fresh dot, and a remove drops the dots it
+//! observed, so an update concurrent with a remove wins. Values are any
+//! [`Semilattice`] and are merged recursively, which lets CRDTs nest. Each dot
+//! carries the nested state written by its update, and the value of a key is
+//! the merge of the states whose dots survive, so merges are associative.
+//!
+//! An update starts from the value it observed, so when it wins over a
+//! concurrent remove, the state the remove dropped comes back with it: a
+//! removed counter that is concurrently incremented keeps its old count.
+//!
+//! ```txt
+//! payload set E, integer[n] V // E: set of (key, dot, nested payload)
+//!   initial ∅, [0, 0, ..., 0]
+//! query get (key k) : payload v
+//!   pre ∃d, p : (k, d, p) ∈ E
+//!   let v = merge of {p | ∃d : (k, d, p) ∈ E}
+//! update update (key k, operation o)
+//!   let g = myID()
+//!   let d = (g, V[g] + 1)
+//!   let p = o(get(k)) // get(k) starts at the nested initial payload
+//!   V[g] := V[g] + 1
+//!   E := E \ {(k, d', p') | ∃d', p' : (k, d', p') ∈ E} ∪ {(k, d, p)}
+//! update remove (key k)
+//!   E := E \ {(k, d, p) | ∃d, p : (k, d, p) ∈ E}
+//! compare (A, B) : boolean b
+//!   let b = (merge(A, B) = B)
+//! merge (A, B) : payload C
+//!   let M = A.E ∩ B.E
+//!   let M' = {(k, d, p) ∈ A.E \ B.E | d ∉ B.V}
+//!   let M'' = {(k, d, p) ∈ B.E \ A.E | d ∉ A.V}
+//!   let C.E = M ∪ M' ∪ M''
+//!   let ∀i ∈ [0, n - 1] : C.V[i] = max(A.V[i], B.V[i])
+//! ```
+
+use std::{collections::BTreeMap, convert::Infallible};
+
+use super::{orswot::surviving, Dot, Semilattice, StateBased, VersionVector};
+use crate::ReplicaId;
+
+/// `value` caches the merge of the nested states in `dots`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry<V> {
+    dots: BTreeMap<Dot, V>,
+    value: V,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ORMap<K, V> {
+    entries: BTreeMap<K, Entry<V>>,
+    clock: VersionVector,
+}
+
+impl<K, V> Default for ORMap<K, V> {
+    fn default() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            clock: VersionVector::new(),
+        }
+    }
+}
+
+impl<K, V> ORMap<K, V>
+where
+    K: Ord,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.entries.get(key).map(|entry| &entry.value)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter().map(|(key, entry)| (key, &entry.value))
+    }
+
+    /// Applies `update` to the value at `key`, starting from `V::default()`
+    /// when the key is absent.
+    pub fn update_key<F>(&mut self, replica: ReplicaId, key: K, update: F)
+    where
+        V: Default + Clone,
+        F: FnOnce(&mut V),
+    {
+        let dot = self.clock.next_dot(replica);
+        self.clock.witness(dot);
+        let entry = self.entries.entry(key).or_insert_with(|| Entry {
+            dots: BTreeMap::new(),
+            value: V::default(),
+        });
+        update(&mut entry.value);
+        entry.dots = BTreeMap::from([(dot, entry.value.clone())]);
+    }
+
+    pub fn remove(&mut self, key: &K) {
+        self.entries.remove(key);
+    }
+}
+
+impl<K, V> Semilattice for ORMap<K, V>
+where
+    K: Ord + Clone,
+    V: Semilattice + Clone + PartialEq,
+{
+    fn compare(&self, other: &Self) -> bool {
+        self.merge(other) == *other
+    }
+
+    fn merge(&self, other: &Self) -> Self {
+        let mut merged: BTreeMap<K, BTreeMap<Dot, V>> = BTreeMap::new();
+        for (this, that) in [(self, other), (other, self)] {
+            for (key, entry) in &this.entries {
+                let others = that.entries.get(key).map(|entry| &entry.dots);
+                let dots = surviving(&entry.dots, others, &that.clock);
+                merged.entry(key.clone()).or_default().extend(dots);
+            }
+        }
+        let entries = merged
+            .into_iter()
+            .filter_map(|(key, dots)| {
+                let mut values = dots.values();
+                let first = values.next()?.clone();
+                let value = values.fold(first, |value, other| value.merge(other));
+                Some((key, Entry { dots, value }))
+            })
+            .collect();
+        Self {
+            entries,
+            clock: self.clock.merge(&other.clock),
+        }
+    }
+}
+
+impl<K, V> StateBased<ORMap<K, V>> for ORMap<K, V> {
+    type Query = fn(&ORMap<K, V>) -> Option<ORMap<K, V>>;
+    type Update = fn(&mut ORMap<K, V>) -> Result<Option<ORMap<K, V>>, Infallible>;
+    type Error = Infallible;
+
+    fn query(&self, query: Self::Query) -> Result<Option<ORMap<K, V>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(&mut self, update: Self::Update) -> Result<Option<ORMap<K, V>>, Self::Error> {
+        update(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_based::{GCounter, Orswot};
+
+    #[test]
+    fn test_update() {
+        let mut map: ORMap<&str, GCounter> = ORMap::new();
+        map.update_key(0, "a", |counter| counter.increment(0));
+        map.update_key(0, "a", |counter| counter.increment(0));
+        map.update_key(0, "b", |counter| counter.increment(0));
+        map.remove(&"b");
+        assert_eq!(map.get(&"a").map(GCounter::value), Some(2));
+        assert!(!map.contains_key(&"b"));
+    }
+
+    #[test]
+    fn test_merge_nested_counters() {
+        let mut map1: ORMap<&str, GCounter> = ORMap::new();
+        map1.update_key(0, "a", |counter| counter.increment(0));
+        let mut map2 = map1.clone();
+        map1.update_key(0, "a", |counter| counter.increment(0));
+        map2.update_key(1, "a", |counter| counter.increment(1));
+        assert!(!map1.compare(&map2));
+
+        let merged = map1.merge(&map2);
+        assert_eq!(merged, map2.merge(&map1));
+        assert!(map1.compare(&merged) && map2.compare(&merged));
+        assert_eq!(merged.get(&"a").map(GCounter::value), Some(3));
+    }
+
+    #[test]
+    fn test_merge_nested_sets() {
+        let mut map1: ORMap<&str, Orswot<&str>> = ORMap::new();
+        map1.update_key(0, "tags", |set| set.add(0, "rust"));
+        let mut map2 = map1.clone();
+        map1.update_key(0, "tags", |set| set.remove(&"rust"));
+        map2.update_key(1, "tags", |set| set.add(1, "crdt"));
+
+        let merged = map1.merge(&map2);
+        let tags = merged.get(&"tags").unwrap();
+        assert_eq!(tags.iter().collect::<Vec<_>>(), vec![&"crdt"]);
+    }
+
+    #[test]
+    fn test_merge_remove() {
+        let mut map1: ORMap<&str, GCounter> = ORMap::new();
+        map1.update_key(0, "a", |counter| counter.increment(0));
+        let mut map2 = map1.clone();
+        map1.remove(&"a");
+
+        // map2 never observed the remove, but its entry is covered by map1.
+        assert!(!map1.merge(&map2).contains_key(&"a"));
+
+        // A concurrent update wins over the remove, and since it built on the
+        // count map2 observed, the removed increment comes back with it.
+        map2.update_key(1, "a", |counter| counter.increment(1));
+        let merged = map1.merge(&map2);
+        assert_eq!(merged.get(&"a").map(GCounter::value), Some(2));
+    }
+
+    #[test]
+    fn test_merge_associative() {
+        let mut map1: ORMap<&str, GCounter> = ORMap::new();
+        map1.update_key(1, "k", |counter| counter.increment(1));
+        let mut map3 = map1.clone();
+        map3.remove(&"k");
+        let mut map2: ORMap<&str, GCounter> = ORMap::new();
+        map2.update_key(2, "k", |counter| counter.increment(2));
+
+        // The increment map3 removed must not get through either grouping.
+        let left = map1.merge(&map2).merge(&map3);
+        let right = map1.merge(&map2.merge(&map3));
+        assert_eq!(left, right);
+        assert_eq!(left.get(&"k").map(GCounter::value), Some(1));
+    }
+
+    #[test]
+    fn test_merge_re_add() {
+        let mut map1: ORMap<&str, GCounter> = ORMap::new();
+        map1.update_key(0, "a", |counter| counter.increment(0));
+        let map2 = map1.clone();
+        map1.remove(&"a");
+        map1.update_key(0, "a", |counter| counter.increment(2));
+
+        // The value map2 holds predates the remove, so it is not merged in.
+        let merged = map1.merge(&map2);
+        assert_eq!(merged, map2.merge(&map1));
+        assert_eq!(merged.get(&"a").map(GCounter::value), Some(1));
+    }
+}
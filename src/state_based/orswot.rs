@@ -0,0 +1,230 @@
+//! State-based optimized observed-remove set without tombstones (ORSWOT)
+//!
+//! Every element carries the dots of the adds that introduced it, and a
+//! version vector summarises every add the replica has observed. A remove
+//! simply drops the element's dots: since they stay covered by the version
+//! vector, a merge can tell a removed element from one it has not seen yet,
+//! and no tombstones are needed. An add concurrent with a remove wins.
+//!
+//! ```txt
+//! payload set E, integer[n] V // E: set of (element, dot) pairs
+//!   initial ∅, [0, 0, ..., 0]
+//! query lookup (element e) : boolean b
+//!   let b = (∃d : (e, d) ∈ E)
+//! update add (element e)
+//!   let g = myID()
+//!   let d = (g, V[g] + 1)
+//!   V[g] := V[g] + 1
+//!   E := E \ {(e, d') | ∃d' : (e, d') ∈ E} ∪ {(e, d)}
+//! update remove (element e)
+//!   E := E \ {(e, d) | ∃d : (e, d) ∈ E}
+//! compare (A, B) : boolean b
+//!   let b = (merge(A, B) = B)
+//! merge (A, B) : payload C
+//!   let M = A.E ∩ B.E
+//!   let M' = {(e, d) ∈ A.E \ B.E | d ∉ B.V}
+//!   let M'' = {(e, d) ∈ B.E \ A.E | d ∉ A.V}
+//!   let C.E = M ∪ M' ∪ M''
+//!   let ∀i ∈ [0, n - 1] : C.V[i] = max(A.V[i], B.V[i])
+//! ```
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::Infallible,
+};
+
+use super::{Dot, ObservedRemoveSet, Semilattice, StateBased, VersionVector};
+use crate::ReplicaId;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Orswot<T> {
+    entries: BTreeMap<T, BTreeSet<Dot>>,
+    clock: VersionVector,
+}
+
+impl<T> Default for Orswot<T> {
+    fn default() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            clock: VersionVector::new(),
+        }
+    }
+}
+
+impl<T> Orswot<T>
+where
+    T: Ord,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lookup(&self, element: &T) -> bool {
+        self.entries.contains_key(element)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.entries.keys()
+    }
+
+    pub fn add(&mut self, replica: ReplicaId, element: T) {
+        let dot = self.clock.next_dot(replica);
+        self.clock.witness(dot);
+        self.entries.insert(element, BTreeSet::from([dot]));
+    }
+
+    pub fn remove(&mut self, element: &T) {
+        self.entries.remove(element);
+    }
+}
+
+/// A collection keyed by dots: a plain set of dots, or a map from dots to
+/// whatever each dotted update contributed.
+pub(super) trait DotStore: Clone {
+    fn contains_dot(&self, dot: &Dot) -> bool;
+
+    fn retain_dots<F: FnMut(&Dot) -> bool>(&mut self, keep: F);
+}
+
+impl DotStore for BTreeSet<Dot> {
+    fn contains_dot(&self, dot: &Dot) -> bool {
+        self.contains(dot)
+    }
+
+    fn retain_dots<F: FnMut(&Dot) -> bool>(&mut self, keep: F) {
+        self.retain(keep);
+    }
+}
+
+impl<V: Clone> DotStore for BTreeMap<Dot, V> {
+    fn contains_dot(&self, dot: &Dot) -> bool {
+        self.contains_key(dot)
+    }
+
+    fn retain_dots<F: FnMut(&Dot) -> bool>(&mut self, mut keep: F) {
+        self.retain(|dot, _| keep(dot));
+    }
+}
+
+/// Dots of `dots` that survive a merge with a replica holding `others` for
+/// the same element and having observed `clock`.
+pub(super) fn surviving<S: DotStore>(dots: &S, others: Option<&S>, clock: &VersionVector) -> S {
+    let mut dots = dots.clone();
+    dots.retain_dots(|dot| {
+        others.is_some_and(|others| others.contains_dot(dot)) || !clock.contains(dot)
+    });
+    dots
+}
+
+impl<T> Semilattice for Orswot<T>
+where
+    T: Ord + Clone,
+{
+    fn compare(&self, other: &Self) -> bool {
+        self.merge(other) == *other
+    }
+
+    fn merge(&self, other: &Self) -> Self {
+        let mut entries = BTreeMap::new();
+        for (element, dots) in &self.entries {
+            let dots = surviving(dots, other.entries.get(element), &other.clock);
+            if !dots.is_empty() {
+                entries.insert(element.clone(), dots);
+            }
+        }
+        for (element, dots) in &other.entries {
+            let dots = surviving(dots, self.entries.get(element), &self.clock);
+            if !dots.is_empty() {
+                entries
+                    .entry(element.clone())
+                    .or_insert_with(BTreeSet::new)
+                    .extend(dots);
+            }
+        }
+        Self {
+            entries,
+            clock: self.clock.merge(&other.clock),
+        }
+    }
+}
+
+/// Adds win over concurrent removes.
+impl<T> ObservedRemoveSet<T> for Orswot<T>
+where
+    T: Ord + Clone,
+{
+    fn lookup(&self, element: &T) -> bool {
+        Orswot::lookup(self, element)
+    }
+
+    fn add(&mut self, replica: ReplicaId, element: T) {
+        Orswot::add(self, replica, element);
+    }
+
+    /// Dropping the observed dots is enough, `replica` is not needed.
+    fn remove(&mut self, _replica: ReplicaId, element: &T) {
+        Orswot::remove(self, element);
+    }
+}
+
+impl<T> StateBased<Orswot<T>> for Orswot<T> {
+    type Query = fn(&Orswot<T>) -> Option<Orswot<T>>;
+    type Update = fn(&mut Orswot<T>) -> Result<Option<Orswot<T>>, Infallible>;
+    type Error = Infallible;
+
+    fn query(&self, query: Self::Query) -> Result<Option<Orswot<T>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(&mut self, update: Self::Update) -> Result<Option<Orswot<T>>, Self::Error> {
+        update(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_remove() {
+        let mut set = Orswot::new();
+        set.add(0, "a");
+        set.add(0, "b");
+        set.remove(&"a");
+        assert!(!set.lookup(&"a"));
+        set.add(0, "a");
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![&"a", &"b"]);
+    }
+
+    #[test]
+    fn test_merge_remove() {
+        let mut set1 = Orswot::new();
+        set1.add(0, "a");
+        let mut set2 = set1.clone();
+        set2.remove(&"a");
+        assert!(set1.compare(&set2));
+        assert!(!set2.compare(&set1));
+
+        // The removed dot is covered by set2's clock, so it is not revived.
+        let merged = set1.merge(&set2);
+        assert_eq!(merged, set2.merge(&set1));
+        assert!(!merged.lookup(&"a"));
+    }
+
+    #[test]
+    fn test_merge_concurrent_add_wins() {
+        let mut set1 = Orswot::new();
+        set1.add(0, "a");
+        let mut set2 = set1.clone();
+        set1.remove(&"a");
+        set1.add(0, "b");
+        set2.add(1, "a");
+        assert!(!set1.compare(&set2));
+        assert!(!set2.compare(&set1));
+
+        let merged = set1.merge(&set2);
+        assert_eq!(merged, set2.merge(&set1));
+        assert!(set1.compare(&merged) && set2.compare(&merged));
+        assert_eq!(merged.iter().collect::<Vec<_>>(), vec![&"a", &"b"]);
+    }
+}
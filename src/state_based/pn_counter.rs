@@ -0,0 +1,118 @@
+//! State-based PN-Counter
+//!
+//! ```txt
+//! payload integer[n] P, integer[n] N
+//!   initial [0, 0, ..., 0], [0, 0, ..., 0]
+//! update increment ()
+//!   let g = myID()
+//!   P[g] := P[g] + 1
+//! update decrement ()
+//!   let g = myID()
+//!   N[g] := N[g] + 1
+//! query value () : integer v
+//!   let v = Σi P[i] - Σi N[i]
+//! compare (X, Y) : boolean b
+//!   let b = (∀i ∈ [0, n - 1] : X.P[i] ≤ Y.P[i] ∧ ∀i ∈ [0, n - 1] : X.N[i] ≤ Y.N[i])
+//! merge (X, Y) : payload Z
+//!   let ∀i ∈ [0, n - 1] : Z.P[i] = max(X.P[i], Y.P[i])
+//!   let ∀i ∈ [0, n - 1] : Z.N[i] = max(X.N[i], Y.N[i])
+//! ```
+
+use std::convert::Infallible;
+
+use super::{GCounter, Semilattice, StateBased};
+use crate::ReplicaId;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PNCounter {
+    p: GCounter,
+    n: GCounter,
+}
+
+impl PNCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn increment(&mut self, replica: ReplicaId) {
+        self.p.increment(replica);
+    }
+
+    pub fn decrement(&mut self, replica: ReplicaId) {
+        self.n.increment(replica);
+    }
+
+    pub fn value(&self) -> i64 {
+        self.p.value() as i64 - self.n.value() as i64
+    }
+}
+
+impl Semilattice for PNCounter {
+    fn compare(&self, other: &Self) -> bool {
+        self.p.compare(&other.p) && self.n.compare(&other.n)
+    }
+
+    fn merge(&self, other: &Self) -> Self {
+        Self {
+            p: self.p.merge(&other.p),
+            n: self.n.merge(&other.n),
+        }
+    }
+}
+
+impl StateBased<PNCounter> for PNCounter {
+    type Query = fn(&PNCounter) -> Option<PNCounter>;
+    type Update = fn(&mut PNCounter) -> Result<Option<PNCounter>, Infallible>;
+    type Error = Infallible;
+
+    fn query(&self, query: Self::Query) -> Result<Option<PNCounter>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(&mut self, update: Self::Update) -> Result<Option<PNCounter>, Self::Error> {
+        update(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value() {
+        let mut counter = PNCounter::new();
+        counter.increment(0);
+        counter.decrement(1);
+        counter.decrement(1);
+        assert_eq!(counter.value(), -1);
+    }
+
+    #[test]
+    fn test_compare() {
+        let mut counter1 = PNCounter::new();
+        counter1.increment(0);
+        let mut counter2 = counter1.clone();
+        counter2.decrement(0);
+        assert!(counter1.compare(&counter2));
+        assert!(!counter2.compare(&counter1));
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut counter1 = PNCounter::new();
+        let mut counter2 = PNCounter::new();
+        counter1.increment(0);
+        counter1.increment(0);
+        counter2.increment(1);
+        counter2.decrement(1);
+        counter2.decrement(1);
+
+        let merged = counter1.merge(&counter2);
+        assert_eq!(merged, counter2.merge(&counter1));
+        assert_eq!(merged.value(), 1);
+
+        // Concurrent decrements are never lost, unlike a max over the value.
+        counter1.decrement(0);
+        assert_eq!(merged.merge(&counter1).value(), 0);
+    }
+}
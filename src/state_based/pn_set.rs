@@ -0,0 +1,159 @@
+//! State-based PN-Set
+//!
+//! Every element carries a PN-Counter incremented by add and decremented by
+//! remove. The element is present while its count is positive, which lets it
+//! be re-added but exhibits anomalies, e.g. concurrent removes can drive the
+//! count below zero so that a later add leaves the element absent.
+//!
+//! ```txt
+//! payload set S // set of (element, PN-Counter) pairs
+//!   initial ∅
+//! query lookup (element e) : boolean b
+//!   let b = (∃(e, c) ∈ S : c.value() > 0)
+//! update add (element e)
+//!   S[e].increment()
+//! update remove (element e)
+//!   S[e].decrement()
+//! compare (S, T) : boolean b
+//!   let b = (∀(e, c) ∈ S : c.compare(T[e]))
+//! merge (S, T) : payload U
+//!   let ∀e : U[e] = S[e].merge(T[e])
+//! ```
+
+use std::{collections::BTreeMap, convert::Infallible};
+
+use super::{PNCounter, Semilattice, StateBased};
+use crate::ReplicaId;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PNSet<T> {
+    s: BTreeMap<T, PNCounter>,
+}
+
+impl<T> Default for PNSet<T> {
+    fn default() -> Self {
+        Self { s: BTreeMap::new() }
+    }
+}
+
+impl<T> PNSet<T>
+where
+    T: Ord,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lookup(&self, element: &T) -> bool {
+        self.count(element) > 0
+    }
+
+    /// Value of the counter attached to `element`.
+    pub fn count(&self, element: &T) -> i64 {
+        self.s.get(element).map_or(0, PNCounter::value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.s
+            .iter()
+            .filter(|(_, counter)| counter.value() > 0)
+            .map(|(element, _)| element)
+    }
+
+    pub fn add(&mut self, replica: ReplicaId, element: T) {
+        self.s.entry(element).or_default().increment(replica);
+    }
+
+    pub fn remove(&mut self, replica: ReplicaId, element: T) {
+        self.s.entry(element).or_default().decrement(replica);
+    }
+}
+
+impl<T> Semilattice for PNSet<T>
+where
+    T: Ord + Clone,
+{
+    fn compare(&self, other: &Self) -> bool {
+        self.s.iter().all(|(element, counter)| {
+            counter.compare(other.s.get(element).unwrap_or(&PNCounter::new()))
+        })
+    }
+
+    fn merge(&self, other: &Self) -> Self {
+        let mut s = self.s.clone();
+        for (element, counter) in &other.s {
+            let entry = s.entry(element.clone()).or_default();
+            *entry = entry.merge(counter);
+        }
+        Self { s }
+    }
+}
+
+impl<T> StateBased<PNSet<T>> for PNSet<T> {
+    type Query = fn(&PNSet<T>) -> Option<PNSet<T>>;
+    type Update = fn(&mut PNSet<T>) -> Result<Option<PNSet<T>>, Infallible>;
+    type Error = Infallible;
+
+    fn query(&self, query: Self::Query) -> Result<Option<PNSet<T>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(&mut self, update: Self::Update) -> Result<Option<PNSet<T>>, Self::Error> {
+        update(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        let mut set = PNSet::new();
+        set.add(0, 1);
+        set.remove(0, 1);
+        assert!(!set.lookup(&1));
+        set.add(0, 1);
+        assert!(set.lookup(&1));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![&1]);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut set1 = PNSet::new();
+        set1.add(0, 1);
+        let mut set2 = set1.clone();
+        set2.remove(1, 1);
+        set1.add(0, 2);
+        assert!(!set1.compare(&set2));
+
+        let merged = set1.merge(&set2);
+        assert_eq!(merged, set2.merge(&set1));
+        assert!(set1.compare(&merged) && set2.compare(&merged));
+        assert_eq!(merged.iter().collect::<Vec<_>>(), vec![&2]);
+    }
+
+    #[test]
+    fn test_concurrent_adds_anomaly() {
+        // A remove only cancels one of two concurrent adds.
+        let mut set1 = PNSet::new();
+        let mut set2 = PNSet::new();
+        set1.add(0, 1);
+        set2.add(1, 1);
+        set1.remove(0, 1);
+
+        let merged = set1.merge(&set2);
+        assert!(merged.lookup(&1));
+
+        // Concurrent removes of the same element both count, so an add
+        // observing them does not bring it back.
+        let mut set3 = merged.clone();
+        let mut set4 = merged;
+        set3.remove(0, 1);
+        set4.remove(1, 1);
+        let mut merged = set3.merge(&set4);
+        assert_eq!(merged.count(&1), -1);
+        merged.add(0, 1);
+        assert!(!merged.lookup(&1));
+    }
+}
@@ -0,0 +1,214 @@
+//! State-based remove-wins set
+//!
+//! The dual of the [`Orswot`](super::Orswot): both adds and removes leave a
+//! dot-tagged token on the element, replacing every token they observed. An
+//! element is present when it holds add tokens only, so a remove concurrent
+//! with an add survives the merge and hides the element. Remove tokens are
+//! kept until a later add observes them.
+//!
+//! ```txt
+//! payload set A, set R, integer[n] V // A, R: sets of (element, dot) pairs
+//!   initial ∅, ∅, [0, 0, ..., 0]
+//! query lookup (element e) : boolean b
+//!   let b = (∃d : (e, d) ∈ A) ∧ (∄d : (e, d) ∈ R)
+//! update add (element e)
+//!   let g = myID()
+//!   let d = (g, V[g] + 1)
+//!   V[g] := V[g] + 1
+//!   A := A \ {(e, d') | ∃d' : (e, d') ∈ A} ∪ {(e, d)}
+//!   R := R \ {(e, d') | ∃d' : (e, d') ∈ R}
+//! update remove (element e)
+//!   let g = myID()
+//!   let d = (g, V[g] + 1)
+//!   V[g] := V[g] + 1
+//!   A := A \ {(e, d') | ∃d' : (e, d') ∈ A}
+//!   R := R \ {(e, d') | ∃d' : (e, d') ∈ R} ∪ {(e, d)}
+//! compare (X, Y) : boolean b
+//!   let b = (merge(X, Y) = Y)
+//! merge (X, Y) : payload Z
+//!   let Z.A, Z.R = merge A and R each like the E of an ORSWOT
+//!   let ∀i ∈ [0, n - 1] : Z.V[i] = max(X.V[i], Y.V[i])
+//! ```
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::Infallible,
+};
+
+use super::{orswot::surviving, Dot, ObservedRemoveSet, Semilattice, StateBased, VersionVector};
+use crate::ReplicaId;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Tokens {
+    adds: BTreeSet<Dot>,
+    removes: BTreeSet<Dot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoveWinsSet<T> {
+    entries: BTreeMap<T, Tokens>,
+    clock: VersionVector,
+}
+
+impl<T> Default for RemoveWinsSet<T> {
+    fn default() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            clock: VersionVector::new(),
+        }
+    }
+}
+
+impl<T> RemoveWinsSet<T>
+where
+    T: Ord + Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lookup(&self, element: &T) -> bool {
+        self.entries
+            .get(element)
+            .is_some_and(|tokens| tokens.removes.is_empty())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.entries.keys().filter(|element| self.lookup(element))
+    }
+
+    pub fn add(&mut self, replica: ReplicaId, element: T) {
+        let dot = self.next_dot(replica);
+        let tokens = Tokens {
+            adds: BTreeSet::from([dot]),
+            removes: BTreeSet::new(),
+        };
+        self.entries.insert(element, tokens);
+    }
+
+    pub fn remove(&mut self, replica: ReplicaId, element: &T) {
+        let dot = self.next_dot(replica);
+        let tokens = Tokens {
+            adds: BTreeSet::new(),
+            removes: BTreeSet::from([dot]),
+        };
+        self.entries.insert(element.clone(), tokens);
+    }
+
+    fn next_dot(&mut self, replica: ReplicaId) -> Dot {
+        let dot = self.clock.next_dot(replica);
+        self.clock.witness(dot);
+        dot
+    }
+}
+
+/// Removes win over concurrent adds.
+impl<T> ObservedRemoveSet<T> for RemoveWinsSet<T>
+where
+    T: Ord + Clone,
+{
+    fn lookup(&self, element: &T) -> bool {
+        RemoveWinsSet::lookup(self, element)
+    }
+
+    fn add(&mut self, replica: ReplicaId, element: T) {
+        RemoveWinsSet::add(self, replica, element);
+    }
+
+    fn remove(&mut self, replica: ReplicaId, element: &T) {
+        RemoveWinsSet::remove(self, replica, element);
+    }
+}
+
+impl<T> Semilattice for RemoveWinsSet<T>
+where
+    T: Ord + Clone,
+{
+    fn compare(&self, other: &Self) -> bool {
+        self.merge(other) == *other
+    }
+
+    fn merge(&self, other: &Self) -> Self {
+        let mut entries: BTreeMap<T, Tokens> = BTreeMap::new();
+        for (this, that) in [(self, other), (other, self)] {
+            for (element, tokens) in &this.entries {
+                let others = that.entries.get(element);
+                let adds = surviving(&tokens.adds, others.map(|t| &t.adds), &that.clock);
+                let removes = surviving(&tokens.removes, others.map(|t| &t.removes), &that.clock);
+                if adds.is_empty() && removes.is_empty() {
+                    continue;
+                }
+                let entry = entries.entry(element.clone()).or_default();
+                entry.adds.extend(adds);
+                entry.removes.extend(removes);
+            }
+        }
+        Self {
+            entries,
+            clock: self.clock.merge(&other.clock),
+        }
+    }
+}
+
+impl<T> StateBased<RemoveWinsSet<T>> for RemoveWinsSet<T> {
+    type Query = fn(&RemoveWinsSet<T>) -> Option<RemoveWinsSet<T>>;
+    type Update = fn(&mut RemoveWinsSet<T>) -> Result<Option<RemoveWinsSet<T>>, Infallible>;
+    type Error = Infallible;
+
+    fn query(&self, query: Self::Query) -> Result<Option<RemoveWinsSet<T>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(&mut self, update: Self::Update) -> Result<Option<RemoveWinsSet<T>>, Self::Error> {
+        update(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_based::Orswot;
+
+    /// Replica 0 removes "a" while replica 1 adds it again.
+    fn concurrent_add_remove<S: ObservedRemoveSet<&'static str> + Clone>(mut set1: S) -> S {
+        set1.add(0, "a");
+        let mut set2 = set1.clone();
+        set1.remove(0, &"a");
+        set2.add(1, "a");
+        assert_eq!(
+            set1.merge(&set2).lookup(&"a"),
+            set2.merge(&set1).lookup(&"a")
+        );
+        set1.merge(&set2)
+    }
+
+    #[test]
+    fn test_add_remove() {
+        let mut set = RemoveWinsSet::new();
+        set.add(0, "a");
+        set.add(0, "b");
+        set.remove(0, &"a");
+        assert!(!set.lookup(&"a"));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![&"b"]);
+        set.add(0, "a");
+        assert!(set.lookup(&"a"));
+    }
+
+    #[test]
+    fn test_merge_sequential() {
+        let mut set1 = RemoveWinsSet::new();
+        set1.add(0, "a");
+        set1.remove(0, &"a");
+        let mut set2 = set1.clone();
+        set2.add(1, "a");
+        assert!(set1.compare(&set2));
+        assert!(!set2.compare(&set1));
+        assert!(set1.merge(&set2).lookup(&"a"));
+    }
+
+    #[test]
+    fn test_policies() {
+        assert!(!concurrent_add_remove(RemoveWinsSet::new()).lookup(&"a"));
+        assert!(concurrent_add_remove(Orswot::new()).lookup(&"a"));
+    }
+}
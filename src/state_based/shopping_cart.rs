@@ -0,0 +1,239 @@
+//! State-based shopping cart
+//!
+//! The use case motivating Dynamo's replicated cart. Dynamo merged carts by
+//! union, so an item removed at one replica reappeared when merged with a
+//! replica that had not seen the remove. Here every product line is a set of
+//! dotted contributions, tracked like the entries of an [`Orswot`](super::Orswot):
+//! a remove or quantity change drops the contributions it observed, so it only
+//! loses against adds or changes concurrent with it.
+//!
+//! An add contributes its amount on top of the line. A quantity change
+//! contributes the new quantity itself; concurrent changes do not add up, the
+//! largest one wins, and concurrent adds are counted on top of it.
+//!
+//! ```txt
+//! payload set E, integer[n] V // E: set of (product, dot, contribution)
+//!   initial ∅, [0, 0, ..., 0]
+//! query quantity (product p) : integer q
+//!   let S = {n | ∃d : (p, d, set(n)) ∈ E}
+//!   let A = {n | ∃d : (p, d, add(n)) ∈ E}
+//!   let q = max(S ∪ {0}) + Σ A
+//! update add (product p, integer n)
+//!   let g = myID()
+//!   let d = (g, V[g] + 1)
+//!   V[g] := V[g] + 1
+//!   E := E ∪ {(p, d, add(n))}
+//! update changeQuantity (product p, integer n)
+//!   let g = myID()
+//!   let d = (g, V[g] + 1)
+//!   V[g] := V[g] + 1
+//!   E := E \ {(p, d', c) | ∃d', c : (p, d', c) ∈ E} ∪ {(p, d, set(n))}
+//! update remove (product p)
+//!   E := E \ {(p, d, c) | ∃d, c : (p, d, c) ∈ E}
+//! compare (A, B) : boolean b
+//!   let b = (merge(A, B) = B)
+//! merge (A, B) : payload C
+//!   let M = A.E ∩ B.E
+//!   let M' = {(p, d, c) ∈ A.E \ B.E | d ∉ B.V}
+//!   let M'' = {(p, d, c) ∈ B.E \ A.E | d ∉ A.V}
+//!   let C.E = M ∪ M' ∪ M''
+//!   let ∀i ∈ [0, n - 1] : C.V[i] = max(A.V[i], B.V[i])
+//! ```
+
+use std::{collections::BTreeMap, convert::Infallible};
+
+use super::{orswot::surviving, Dot, Semilattice, StateBased, VersionVector};
+use crate::ReplicaId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Contribution {
+    Add(u64),
+    Set(u64),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShoppingCart<K> {
+    lines: BTreeMap<K, BTreeMap<Dot, Contribution>>,
+    clock: VersionVector,
+}
+
+impl<K> Default for ShoppingCart<K> {
+    fn default() -> Self {
+        Self {
+            lines: BTreeMap::new(),
+            clock: VersionVector::new(),
+        }
+    }
+}
+
+/// Quantities saturate at `u64::MAX` instead of wrapping.
+fn quantity(contributions: &BTreeMap<Dot, Contribution>) -> u64 {
+    let (mut set, mut added) = (0u64, 0u64);
+    for contribution in contributions.values() {
+        match *contribution {
+            Contribution::Add(amount) => added = added.saturating_add(amount),
+            Contribution::Set(quantity) => set = set.max(quantity),
+        }
+    }
+    set.saturating_add(added)
+}
+
+impl<K> ShoppingCart<K>
+where
+    K: Ord,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn quantity(&self, product: &K) -> u64 {
+        self.lines.get(product).map_or(0, quantity)
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = (&K, u64)> {
+        self.lines
+            .iter()
+            .map(|(product, contributions)| (product, quantity(contributions)))
+            .filter(|(_, quantity)| *quantity > 0)
+    }
+
+    pub fn add(&mut self, replica: ReplicaId, product: K, quantity: u64) {
+        let dot = self.clock.next_dot(replica);
+        self.clock.witness(dot);
+        self.lines
+            .entry(product)
+            .or_default()
+            .insert(dot, Contribution::Add(quantity));
+    }
+
+    /// Replaces every contribution observed so far, so only adds and changes
+    /// concurrent with this one are counted on top of `quantity`.
+    pub fn change_quantity(&mut self, replica: ReplicaId, product: K, quantity: u64) {
+        let dot = self.clock.next_dot(replica);
+        self.clock.witness(dot);
+        self.lines.insert(
+            product,
+            BTreeMap::from([(dot, Contribution::Set(quantity))]),
+        );
+    }
+
+    pub fn remove(&mut self, product: &K) {
+        self.lines.remove(product);
+    }
+}
+
+impl<K> Semilattice for ShoppingCart<K>
+where
+    K: Ord + Clone,
+{
+    fn compare(&self, other: &Self) -> bool {
+        self.merge(other) == *other
+    }
+
+    fn merge(&self, other: &Self) -> Self {
+        let mut lines: BTreeMap<K, BTreeMap<Dot, Contribution>> = BTreeMap::new();
+        for (this, that) in [(self, other), (other, self)] {
+            for (product, contributions) in &this.lines {
+                let others = that.lines.get(product);
+                let dots = surviving(contributions, others, &that.clock);
+                lines.entry(product.clone()).or_default().extend(dots);
+            }
+        }
+        lines.retain(|_, contributions| !contributions.is_empty());
+        Self {
+            lines,
+            clock: self.clock.merge(&other.clock),
+        }
+    }
+}
+
+impl<K> StateBased<ShoppingCart<K>> for ShoppingCart<K> {
+    type Query = fn(&ShoppingCart<K>) -> Option<ShoppingCart<K>>;
+    type Update = fn(&mut ShoppingCart<K>) -> Result<Option<ShoppingCart<K>>, Infallible>;
+    type Error = Infallible;
+
+    fn query(&self, query: Self::Query) -> Result<Option<ShoppingCart<K>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(&mut self, update: Self::Update) -> Result<Option<ShoppingCart<K>>, Self::Error> {
+        update(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_based::Payload;
+
+    #[test]
+    fn test_quantities() {
+        let mut cart = ShoppingCart::new();
+        cart.add(0, "book", 2);
+        cart.add(0, "book", 1);
+        cart.add(0, "pen", 1);
+        cart.change_quantity(0, "pen", 5);
+        assert_eq!(cart.quantity(&"book"), 3);
+        assert_eq!(cart.quantity(&"pen"), 5);
+
+        cart.change_quantity(0, "book", 1);
+        cart.remove(&"pen");
+        assert_eq!(cart.items().collect::<Vec<_>>(), vec![(&"book", 1)]);
+
+        cart.add(0, "book", u64::MAX);
+        assert_eq!(cart.quantity(&"book"), u64::MAX);
+    }
+
+    #[test]
+    fn test_deleted_item_does_not_reappear() {
+        let mut replica1 = ShoppingCart::new();
+        replica1.add(0, "book", 1);
+        replica1.add(0, "pen", 1);
+        let replica2 = replica1.clone();
+
+        // Replica 1 deletes the book; replica 2 still holds the stale cart,
+        // which a union merge would bring the book back from.
+        replica1.remove(&"book");
+        let mut payload = Payload::new(replica1);
+        payload.merge(&Payload::new(replica2));
+
+        let cart = payload.query(|cart| Some(cart.clone())).unwrap().unwrap();
+        assert_eq!(cart.items().collect::<Vec<_>>(), vec![(&"pen", 1)]);
+    }
+
+    #[test]
+    fn test_concurrent_add_wins() {
+        let mut replica1 = ShoppingCart::new();
+        replica1.add(0, "book", 4);
+        let mut replica2 = replica1.clone();
+        replica1.remove(&"book");
+        replica2.add(1, "book", 1);
+
+        // Only the concurrent add survives, the removed quantity does not.
+        let merged = replica1.merge(&replica2);
+        assert_eq!(merged, replica2.merge(&replica1));
+        assert!(replica1.compare(&merged) && replica2.compare(&merged));
+        assert_eq!(merged.quantity(&"book"), 1);
+    }
+
+    #[test]
+    fn test_concurrent_change_quantity() {
+        let mut replica1 = ShoppingCart::new();
+        replica1.add(0, "book", 2);
+        let mut replica2 = replica1.clone();
+        replica1.change_quantity(0, "book", 5);
+        replica2.change_quantity(1, "book", 3);
+
+        // Concurrent changes do not add up, the largest one wins.
+        let merged = replica1.merge(&replica2);
+        assert_eq!(merged, replica2.merge(&replica1));
+        assert_eq!(merged.quantity(&"book"), 5);
+
+        // An add concurrent with a change is counted on top of it.
+        let mut replica3 = merged.clone();
+        let mut replica4 = merged;
+        replica3.change_quantity(0, "book", 1);
+        replica4.add(1, "book", 2);
+        assert_eq!(replica3.merge(&replica4).quantity(&"book"), 3);
+    }
+}
@@ -0,0 +1,174 @@
+//! State-based two-phase set (2P-Set)
+//!
+//! An element may be added and removed, but never added again once removed.
+//!
+//! ```txt
+//! payload set A, set R // A: added; R: removed
+//!   initial ∅, ∅
+//! query lookup (element e) : boolean b
+//!   let b = (e ∈ A ∧ e ∉ R)
+//! update add (element e)
+//!   A := A ∪ {e}
+//! update remove (element e)
+//!   pre lookup(e)
+//!   R := R ∪ {e}
+//! compare (S, T) : boolean b
+//!   let b = (S.A ⊆ T.A ∧ S.R ⊆ T.R)
+//! merge (S, T) : payload U
+//!   let U.A = S.A ∪ T.A
+//!   let U.R = S.R ∪ T.R
+//! ```
+
+use std::{collections::BTreeSet, error, fmt};
+
+use super::{Semilattice, StateBased};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TwoPSetError {
+    NotAdded,
+    AlreadyRemoved,
+}
+
+impl fmt::Display for TwoPSetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAdded => write!(f, "element was never added"),
+            Self::AlreadyRemoved => write!(f, "element was already removed"),
+        }
+    }
+}
+
+impl error::Error for TwoPSetError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TwoPSet<T> {
+    a: BTreeSet<T>,
+    r: BTreeSet<T>,
+}
+
+impl<T> Default for TwoPSet<T> {
+    fn default() -> Self {
+        Self {
+            a: BTreeSet::new(),
+            r: BTreeSet::new(),
+        }
+    }
+}
+
+impl<T> TwoPSet<T>
+where
+    T: Ord + Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lookup(&self, element: &T) -> bool {
+        self.a.contains(element) && !self.r.contains(element)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.a.difference(&self.r)
+    }
+
+    pub fn add(&mut self, element: T) {
+        self.a.insert(element);
+    }
+
+    pub fn remove(&mut self, element: &T) -> Result<(), TwoPSetError> {
+        if !self.a.contains(element) {
+            return Err(TwoPSetError::NotAdded);
+        }
+        if !self.r.insert(element.clone()) {
+            return Err(TwoPSetError::AlreadyRemoved);
+        }
+        Ok(())
+    }
+}
+
+impl<T> Semilattice for TwoPSet<T>
+where
+    T: Ord + Clone,
+{
+    fn compare(&self, other: &Self) -> bool {
+        self.a.is_subset(&other.a) && self.r.is_subset(&other.r)
+    }
+
+    fn merge(&self, other: &Self) -> Self {
+        Self {
+            a: self.a.union(&other.a).cloned().collect(),
+            r: self.r.union(&other.r).cloned().collect(),
+        }
+    }
+}
+
+impl<T> StateBased<TwoPSet<T>> for TwoPSet<T> {
+    type Query = fn(&TwoPSet<T>) -> Option<TwoPSet<T>>;
+    type Update = fn(&mut TwoPSet<T>) -> Result<Option<TwoPSet<T>>, TwoPSetError>;
+    type Error = TwoPSetError;
+
+    fn query(&self, query: Self::Query) -> Result<Option<TwoPSet<T>>, Self::Error> {
+        Ok(query(self))
+    }
+
+    fn update(&mut self, update: Self::Update) -> Result<Option<TwoPSet<T>>, Self::Error> {
+        update(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_based::Payload;
+
+    #[test]
+    fn test_remove() {
+        let mut set = TwoPSet::new();
+        assert_eq!(set.remove(&1), Err(TwoPSetError::NotAdded));
+
+        set.add(1);
+        set.add(2);
+        set.remove(&1).unwrap();
+        assert!(!set.lookup(&1));
+        assert_eq!(set.remove(&1), Err(TwoPSetError::AlreadyRemoved));
+
+        // Removal is final.
+        set.add(1);
+        assert!(!set.lookup(&1));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![&2]);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut set1 = TwoPSet::new();
+        set1.add(1);
+        let mut set2 = set1.clone();
+        set2.remove(&1).unwrap();
+        set1.add(2);
+        assert!(!set1.compare(&set2));
+        assert!(!set2.compare(&set1));
+
+        let merged = set1.merge(&set2);
+        assert_eq!(merged, set2.merge(&set1));
+        assert!(set1.compare(&merged) && set2.compare(&merged));
+        assert_eq!(merged.iter().collect::<Vec<_>>(), vec![&2]);
+    }
+
+    #[test]
+    fn test_payload_update_error() {
+        let mut payload = Payload::new(TwoPSet::new());
+        payload
+            .update(|set| {
+                set.add(1);
+                Ok(None)
+            })
+            .unwrap();
+
+        let remove = |set: &mut TwoPSet<i32>| {
+            set.remove(&1)?;
+            Ok(None)
+        };
+        assert_eq!(payload.update(remove), Ok(None));
+        assert_eq!(payload.update(remove), Err(TwoPSetError::AlreadyRemoved));
+    }
+}
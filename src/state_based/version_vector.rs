@@ -0,0 +1,196 @@
+//! Version vector
+//!
+//! Maps every replica to the number of events it originated. Version vectors
+//! are only partially ordered: [`PartialOrd::partial_cmp`] reports whether one
+//! happened before (`Less`), after (`Greater`) or is `Equal` to the other, and
+//! `None` when they are concurrent.
+//!
+//! ```txt
+//! payload integer[n] V
+//!   initial [0, 0, ..., 0]
+//! update increment ()
+//!   let g = myID()
+//!   V[g] := V[g] + 1
+//! compare (X, Y) : boolean b
+//!   let b = (∀i ∈ [0, n - 1] : X.V[i] ≤ Y.V[i])
+//! merge (X, Y) : payload Z
+//!   let ∀i ∈ [0, n - 1] : Z.V[i] = max(X.V[i], Y.V[i])
+//! ```
+
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use super::Semilattice;
+use crate::ReplicaId;
+
+/// The `counter`-th event originated at `replica`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Dot {
+    pub replica: ReplicaId,
+    pub counter: u64,
+}
+
+/// Entries are only ever stored once non-zero, so the derived equality agrees
+/// with the pointwise order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VersionVector {
+    counters: BTreeMap<ReplicaId, u64>,
+}
+
+impl VersionVector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, replica: ReplicaId) -> u64 {
+        self.counters.get(&replica).copied().unwrap_or(0)
+    }
+
+    /// Records a new event at `replica` and returns its counter.
+    pub fn increment(&mut self, replica: ReplicaId) -> u64 {
+        let counter = self.counters.entry(replica).or_insert(0);
+        *counter += 1;
+        *counter
+    }
+
+    /// The dot the next event at `replica` will get.
+    pub fn next_dot(&self, replica: ReplicaId) -> Dot {
+        Dot {
+            replica,
+            counter: self.get(replica) + 1,
+        }
+    }
+
+    /// Whether the event `dot` is covered by this version vector.
+    pub fn contains(&self, dot: &Dot) -> bool {
+        dot.counter <= self.get(dot.replica)
+    }
+
+    /// Records that the event `dot`, and every event before it, was seen.
+    pub fn witness(&mut self, dot: Dot) {
+        // Keep the map free of zero entries so equal versions compare equal.
+        if dot.counter == 0 {
+            return;
+        }
+        let counter = self.counters.entry(dot.replica).or_insert(0);
+        *counter = (*counter).max(dot.counter);
+    }
+
+    pub fn concurrent(&self, other: &Self) -> bool {
+        self.partial_cmp(other).is_none()
+    }
+}
+
+impl PartialOrd for VersionVector {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let (mut less, mut greater) = (false, false);
+        for replica in self.counters.keys().chain(other.counters.keys()) {
+            match self.get(*replica).cmp(&other.get(*replica)) {
+                Ordering::Less => less = true,
+                Ordering::Greater => greater = true,
+                Ordering::Equal => {}
+            }
+        }
+        match (less, greater) {
+            (false, false) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (true, true) => None,
+        }
+    }
+}
+
+impl Semilattice for VersionVector {
+    fn compare(&self, other: &Self) -> bool {
+        self <= other
+    }
+
+    fn merge(&self, other: &Self) -> Self {
+        let mut counters = self.counters.clone();
+        for (replica, counter) in &other.counters {
+            let entry = counters.entry(*replica).or_insert(0);
+            *entry = (*entry).max(*counter);
+        }
+        Self { counters }
+    }
+
+    fn partial_compare(&self, other: &Self) -> Option<Ordering> {
+        self.partial_cmp(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_cmp() {
+        let mut version1 = VersionVector::new();
+        version1.increment(0);
+        let mut version2 = version1.clone();
+        assert_eq!(version1.partial_cmp(&version2), Some(Ordering::Equal));
+
+        version2.increment(1);
+        assert_eq!(version1.partial_cmp(&version2), Some(Ordering::Less));
+        assert_eq!(version2.partial_cmp(&version1), Some(Ordering::Greater));
+
+        version1.increment(0);
+        assert_eq!(version1.partial_cmp(&version2), None);
+        assert!(version1.concurrent(&version2));
+        assert_eq!(version1.partial_compare(&version2), None);
+    }
+
+    #[test]
+    fn test_dots() {
+        let mut version = VersionVector::new();
+        let dot = version.next_dot(0);
+        assert!(!version.contains(&dot));
+        version.witness(dot);
+        assert!(version.contains(&dot));
+        assert_eq!(version.next_dot(0).counter, 2);
+
+        version.witness(Dot {
+            replica: 1,
+            counter: 3,
+        });
+        assert_eq!(version.get(1), 3);
+    }
+
+    #[test]
+    fn test_compare() {
+        let mut version1 = VersionVector::new();
+        let mut version2 = VersionVector::new();
+        version1.increment(0);
+        assert!(version2.compare(&version1));
+        assert!(!version1.compare(&version2));
+
+        version2.increment(1);
+        assert!(!version1.compare(&version2));
+        assert!(!version2.compare(&version1));
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut version1 = VersionVector::new();
+        let mut version2 = VersionVector::new();
+        assert_eq!(version1.increment(0), 1);
+        assert_eq!(version1.increment(0), 2);
+        version2.increment(0);
+        version2.increment(1);
+
+        let merged = version1.merge(&version2);
+        assert_eq!(merged, version2.merge(&version1));
+        assert_eq!(merged.get(0), 2);
+        assert_eq!(merged.get(1), 1);
+        assert!(version1 < merged && version2 < merged);
+    }
+
+    #[test]
+    fn test_witness_zero() {
+        let mut version = VersionVector::new();
+        version.witness(Dot {
+            replica: 0,
+            counter: 0,
+        });
+        assert_eq!(version, VersionVector::new());
+    }
+}